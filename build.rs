@@ -0,0 +1,31 @@
+//! Lists the bundled CodeJam solutions so the binary can embed every file in
+//! `code-samples/solutions` without naming each one in the source.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let dir = Path::new("code-samples/solutions");
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .expect("code-samples/solutions is missing")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+
+    let mut out = String::from("&[\n");
+    for name in names {
+        out.push_str(&format!(
+            "    ({:?}, include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/code-samples/solutions/\", {:?}))),\n",
+            name, name
+        ));
+    }
+    out.push_str("]\n");
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("code_samples.rs");
+    fs::write(path, out).expect("Failed to write the code sample list");
+}
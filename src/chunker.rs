@@ -0,0 +1,277 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Code up to this long is practiced whole; longer files are split into
+/// units of about `MIN_LINES` to `MAX_LINES` lines
+pub const MAX_LINES: usize = 60;
+const MIN_LINES: usize = 20;
+
+/// A piece of a source file that fits the CodeJam pane.
+pub struct Chunk {
+    /// 1-based line in the file the chunk starts on
+    pub line: usize,
+    /// With trailing whitespace and common indentation removed
+    pub code: String,
+}
+
+struct Line<'a> {
+    text: &'a str,
+    /// How many blocks (brackets, or indentation in Python) enclose the line
+    depth: usize,
+    /// Brackets still open after the line
+    end_depth: usize,
+    /// The line starts inside a multi-line string or comment
+    in_literal: bool,
+}
+
+impl Line<'_> {
+    fn is_blank(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Starts with a closing bracket, so it ends a block rather than
+    /// starting anything
+    fn closes(&self) -> bool {
+        self.text.trim_start().starts_with(['}', ')', ']'])
+    }
+}
+
+/// Split code into coherent units: whole files when they are short enough,
+/// otherwise top-level items (functions, impl blocks, classes) grouped up to
+/// `MAX_LINES`, and the members of any item that is too long on its own.
+/// `language` is a file extension, as in `CodeSample`.
+pub fn chunk(code: &str, language: &str) -> Vec<Chunk> {
+    let lines = if code.lines().count() <= MAX_LINES {
+        // Short enough already, so skip the parse
+        code.lines()
+            .map(|text| Line {
+                text: text.trim_end(),
+                depth: 0,
+                end_depth: 0,
+                in_literal: false,
+            })
+            .collect()
+    } else {
+        scan(code, language)
+    };
+
+    let Some(first) = lines.iter().position(|l| !l.is_blank()) else {
+        return Vec::new();
+    };
+    let last = lines.iter().rposition(|l| !l.is_blank()).unwrap_or(first);
+
+    let mut ranges = Vec::new();
+    split(&lines, first..last + 1, 0, &mut ranges);
+    ranges
+        .into_iter()
+        .map(|range| Chunk {
+            line: range.start + 1,
+            code: dedent(&lines[range]),
+        })
+        .collect()
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Work out each line's nesting depth, using syntect's scopes to ignore
+/// brackets inside strings and comments.
+fn scan<'a>(code: &'a str, language: &str) -> Vec<Line<'a>> {
+    let syntaxes = syntax_set();
+    // syntect has no TypeScript or JSX syntax, and JavaScript's is close
+    // enough for finding brackets
+    let extension = match language {
+        "ts" | "tsx" | "jsx" => "js",
+        other => other,
+    };
+    let syntax = syntaxes
+        .find_syntax_by_extension(extension)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let literals = [
+        Scope::new("comment").expect("valid scope"),
+        Scope::new("string").expect("valid scope"),
+    ];
+    let is_literal = |stack: &ScopeStack| {
+        stack
+            .as_slice()
+            .iter()
+            .any(|scope| literals.iter().any(|l| l.is_prefix_of(*scope)))
+    };
+    let indented = language == "py";
+
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut brackets = 0usize;
+    let mut indents = vec![0];
+    let mut lines = Vec::new();
+
+    for line in LinesWithEndings::from(code) {
+        let text = line.trim_end();
+        let in_literal = is_literal(&stack);
+        if indented && !text.is_empty() && !in_literal && brackets == 0 {
+            let indent = text.len() - text.trim_start().len();
+            while indent < *indents.last().unwrap_or(&0) {
+                indents.pop();
+            }
+            if indent > *indents.last().unwrap_or(&0) {
+                indents.push(indent);
+            }
+        }
+        let depth = brackets + if indented { indents.len() - 1 } else { 0 };
+
+        let ops = state.parse_line(line, syntaxes).unwrap_or_default();
+        let mut ops = ops.iter().peekable();
+        for (i, ch) in line.char_indices() {
+            while let Some((_, op)) = ops.next_if(|(pos, _)| *pos <= i) {
+                let _ = stack.apply(op);
+            }
+            if is_literal(&stack) {
+                continue;
+            }
+            match ch {
+                '{' | '(' | '[' => brackets += 1,
+                '}' | ')' | ']' => brackets = brackets.saturating_sub(1),
+                _ => {}
+            }
+        }
+        for (_, op) in ops {
+            let _ = stack.apply(op);
+        }
+
+        lines.push(Line {
+            text,
+            depth,
+            end_depth: brackets,
+            in_literal,
+        });
+    }
+    lines
+}
+
+/// Split `range` (which starts and ends on non-blank lines) into units of
+/// at most `MAX_LINES`, breaking at blank lines followed by a line at
+/// `depth`, and descending into items that are too long on their own.
+fn split(lines: &[Line], range: Range<usize>, depth: usize, out: &mut Vec<Range<usize>>) {
+    if range.len() <= MAX_LINES {
+        out.push(range);
+        return;
+    }
+
+    let units = units(lines, range.clone(), depth);
+    if units.len() == 1 {
+        // One long item: drop its opening and closing lines and split its
+        // body one level down. Once nothing is nested any deeper, the body
+        // is empty and it's cut by length instead.
+        let mut inner = range.clone().filter(|&i| {
+            let line = &lines[i];
+            line.depth > depth && !line.is_blank() && !(line.closes() && line.end_depth <= depth)
+        });
+        match (inner.clone().next(), inner.next_back()) {
+            (Some(start), Some(end)) => split(lines, start..end + 1, depth + 1, out),
+            _ => hard_split(lines, range, out),
+        }
+        return;
+    }
+
+    // Group neighbouring short items until each group is worth a run
+    let mut current: Option<Range<usize>> = None;
+    for unit in units {
+        if unit.len() > MAX_LINES {
+            if let Some(group) = current.take() {
+                emit(out, group);
+            }
+            split(lines, unit, depth, out);
+            continue;
+        }
+        current = match current {
+            Some(group) if group.len() < MIN_LINES && unit.end - group.start <= MAX_LINES => {
+                Some(group.start..unit.end)
+            }
+            Some(group) => {
+                emit(out, group);
+                Some(unit)
+            }
+            None => Some(unit),
+        };
+    }
+    if let Some(group) = current {
+        emit(out, group);
+    }
+}
+
+/// Add a group to the output. A short one (the doc comment before an item
+/// that had to be split, or the statements after it) joins the previous
+/// chunk instead when they fit together.
+fn emit(out: &mut Vec<Range<usize>>, group: Range<usize>) {
+    match out.last_mut() {
+        Some(last) if group.len() < MIN_LINES && group.end - last.start <= MAX_LINES => {
+            last.end = group.end
+        }
+        _ => out.push(group),
+    }
+}
+
+/// Items in `range` at `depth`, each with any leading comments or
+/// attributes and without trailing blank lines. Items are separated by
+/// blank lines, or failing that, by the end of each statement.
+fn units(lines: &[Line], range: Range<usize>, depth: usize) -> Vec<Range<usize>> {
+    let starts_item = |i: usize| {
+        let line = &lines[i];
+        !line.is_blank() && !line.in_literal && !line.closes() && line.depth <= depth
+    };
+    let mut starts: Vec<usize> = (range.start + 1..range.end)
+        .filter(|&i| starts_item(i) && lines[i - 1].is_blank())
+        .collect();
+    if starts.is_empty() {
+        starts = (range.start + 1..range.end)
+            .filter(|&i| starts_item(i) && lines[i - 1].end_depth <= depth)
+            .collect();
+    }
+    starts.insert(0, range.start);
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let mut end = starts.get(n + 1).copied().unwrap_or(range.end);
+            while end > start && lines[end - 1].is_blank() {
+                end -= 1;
+            }
+            start..end
+        })
+        .collect()
+}
+
+/// Last resort for long stretches with no blank lines to break at.
+fn hard_split(lines: &[Line], range: Range<usize>, out: &mut Vec<Range<usize>>) {
+    let mut start = range.start;
+    while start < range.end {
+        let mut end = (start + MAX_LINES).min(range.end);
+        while end > start && lines[end - 1].is_blank() {
+            end -= 1;
+        }
+        out.push(start..end);
+        start = end;
+        while start < range.end && lines[start].is_blank() {
+            start += 1;
+        }
+    }
+}
+
+fn dedent(lines: &[Line]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_blank())
+        .map(|line| line.text.len() - line.text.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.text.get(indent..).unwrap_or(line.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,211 @@
+use crate::custom_text::{TextInput, TextOptions};
+use crate::exercises::{
+    code_language_key, code_language_name, CODE_DIFFICULTIES, CODE_LANGUAGES, SOURCE_LANGUAGES,
+};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: typehero [OPTIONS]
+       typehero text [TEXT OPTIONS] <FILE|->
+       typehero code [CODE OPTIONS]
+
+Commands:
+  text <FILE>                 Practice on a document (- reads stdin)
+  code                        Start a random CodeJam problem
+
+Text options:
+  --clipboard                 Practice on the clipboard contents instead of a file
+  --keyboard-only             Replace typographic punctuation and drop untypeable characters
+  --chunk <CHARS>             Approximate characters per practice chunk (default 400)
+  -l, --lang <CODE>           Record stats under this language (see below)
+
+Code options (override the saved CodeJam filters for this run):
+  --lang <LANGS>              Comma-separated: javascript, typescript, python, rust, go
+  --difficulty <TIERS>        Comma-separated: easy, medium, hard
+  --from <PATH>               Practice on code from a local repository or directory
+                              instead (respects .gitignore; --lang still applies,
+                              and also takes c, cpp, java, csharp, ruby, php, scala,
+                              shell, lua, haskell)
+
+Options:
+  -w, --wordlist <NAME|PATH>  Word list for Random Words and Quick Drill
+                              (built-in: common, code; or ~/.typehero/wordlists/<NAME>.txt)
+  -l, --lang <CODE>           Practice language: en, de, es, fr, pt, pl (default en).
+                              Stats are kept separately per language
+  -h, --help                  Print help
+  -V, --version               Print version";
+
+pub enum Command {
+    Menu,
+    Text {
+        input: TextInput,
+        options: TextOptions,
+    },
+    /// Empty lists fall back to the filters saved in the config
+    Code {
+        languages: Vec<String>,
+        difficulties: Vec<String>,
+        /// Directory or file to take the code from instead of the problems
+        from: Option<PathBuf>,
+    },
+    Help,
+    Version,
+}
+
+pub struct Cli {
+    pub command: Command,
+    pub wordlist: Option<String>,
+    pub language: Option<String>,
+}
+
+impl Cli {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Cli {
+            command: Command::Menu,
+            wordlist: None,
+            language: None,
+        };
+        let mut args = args.into_iter().peekable();
+
+        if args.peek().map(String::as_str) == Some("text") {
+            args.next();
+            cli.command = parse_text(&mut args, &mut cli.language)?;
+            return Ok(cli);
+        }
+        if args.peek().map(String::as_str) == Some("code") {
+            args.next();
+            cli.command = parse_code(&mut args)?;
+            return Ok(cli);
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => cli.command = Command::Help,
+                "-V" | "--version" => cli.command = Command::Version,
+                "-w" | "--wordlist" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("{} requires a value", arg))?;
+                    cli.wordlist = Some(value);
+                }
+                "-l" | "--lang" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("{} requires a value", arg))?;
+                    cli.language = Some(value);
+                }
+                _ => {
+                    if let Some(value) = arg.strip_prefix("--wordlist=") {
+                        cli.wordlist = Some(value.to_string());
+                    } else if let Some(value) = arg.strip_prefix("--lang=") {
+                        cli.language = Some(value.to_string());
+                    } else {
+                        return Err(format!("Unexpected argument '{}'", arg));
+                    }
+                }
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+fn parse_text(
+    args: &mut impl Iterator<Item = String>,
+    language: &mut Option<String>,
+) -> Result<Command, String> {
+    let mut input = None;
+    let mut options = TextOptions::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--clipboard" => input = Some(TextInput::Clipboard),
+            "--keyboard-only" => options.keyboard_only = true,
+            "--chunk" => {
+                let value = args.next().ok_or("--chunk requires a value")?;
+                options.chunk_chars = value
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| format!("Invalid chunk size '{}'", value))?;
+            }
+            "-l" | "--lang" => {
+                *language = Some(args.next().ok_or("--lang requires a value")?);
+            }
+            "-" => input = Some(TextInput::Stdin),
+            "-h" | "--help" => return Ok(Command::Help),
+            _ if arg.starts_with('-') => {
+                return Err(format!("Unexpected argument '{}'", arg));
+            }
+            _ => input = Some(TextInput::File(PathBuf::from(arg))),
+        }
+    }
+
+    let input = input.ok_or("text requires a file, - for stdin, or --clipboard")?;
+    Ok(Command::Text { input, options })
+}
+
+fn parse_code(args: &mut impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut languages = Vec::new();
+    let mut difficulties = Vec::new();
+    let mut from = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => {
+                let value = args.next().ok_or("--lang requires a value")?;
+                for name in value.split(',').map(str::trim) {
+                    let language = code_language_key(name).ok_or_else(|| {
+                        let keys: Vec<&str> = SOURCE_LANGUAGES.iter().map(|l| l.key).collect();
+                        format!(
+                            "Unknown code language '{}'. Available: {}",
+                            name,
+                            keys.join(", ")
+                        )
+                    })?;
+                    languages.push(language.to_string());
+                }
+            }
+            "--difficulty" => {
+                let value = args.next().ok_or("--difficulty requires a value")?;
+                for tier in value.split(',').map(str::trim) {
+                    if !CODE_DIFFICULTIES.contains(&tier) {
+                        return Err(format!(
+                            "Unknown difficulty '{}'. Available: {}",
+                            tier,
+                            CODE_DIFFICULTIES.join(", ")
+                        ));
+                    }
+                    difficulties.push(tier.to_string());
+                }
+            }
+            "--from" => {
+                from = Some(PathBuf::from(args.next().ok_or("--from requires a path")?));
+            }
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    // Only the bundled problems have difficulty tiers
+    if from.is_some() && !difficulties.is_empty() {
+        return Err("--difficulty can't be combined with --from".to_string());
+    }
+    if from.is_none() {
+        if let Some(language) = languages
+            .iter()
+            .find(|l| !CODE_LANGUAGES.contains(&l.as_str()))
+        {
+            return Err(format!(
+                "No bundled CodeJam problems in {} (available: {}); use --from to practice on your own code",
+                code_language_name(language),
+                CODE_LANGUAGES.join(", ")
+            ));
+        }
+    }
+    Ok(Command::Code {
+        languages,
+        difficulties,
+        from,
+    })
+}
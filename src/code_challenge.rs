@@ -1,301 +1,501 @@
-use crate::display::Display;
+use crate::display::{text_width, Display};
+use crate::engine::show_summary_with_notes;
+use crate::exercises::{code_language_name, CodeSample, Problem};
 use crate::input::{read_key, InputEvent};
-use crate::stats::Stats;
-use crossterm::{
-    cursor,
-    style::{Color, Print, SetForegroundColor},
-    QueueableCommand,
-};
-use std::io::{stdout, Write};
+use crate::render::{Frame, Style};
+use crate::stats::{SessionSummary, Stats, CODE_LANGUAGE};
+use crate::theme::Theme;
+use chrono::Utc;
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Style, ThemeSet};
+use syntect::highlighting::{self, ThemeSet};
 use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 
+/// First screen row of the code panes, below the header
+const CODE_TOP: u16 = 5;
+
+/// Lines kept in view above and below the cursor as the panes scroll
+const SCROLL_MARGIN: usize = 3;
+
+/// How CodeJam scores the whitespace in the code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Whitespace {
+    /// Every space, tab and newline is typed
+    #[default]
+    Exact,
+    /// Enter fills in the next line's indentation, like an editor
+    AutoIndent,
+    /// One space, Tab or Enter covers a whole run of whitespace
+    Collapse,
+}
+
+impl Whitespace {
+    pub const ALL: [Whitespace; 3] = [Self::Exact, Self::AutoIndent, Self::Collapse];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::AutoIndent => "auto-indent",
+            Self::Collapse => "collapse",
+        }
+    }
+}
+
 pub struct CodeChallenge {
     code: String,
     language: String,
+    problem: Option<Problem>,
+    source: Option<String>,
     typed: Vec<char>,
     errors: usize,
+    whitespace: Whitespace,
+    /// Characters filled in by the whitespace mode rather than typed, which
+    /// don't count toward WPM or accuracy
+    skipped: usize,
+    /// The last character was filled in, so there's no bigram to time
+    after_skip: bool,
     start_time: Option<Instant>,
-    last_typed_len: usize,
+    last_char_time: Option<Instant>,
+    /// First line of code in view
+    scroll: usize,
 }
 
 impl CodeChallenge {
-    pub fn new(code: String, language: String) -> Self {
+    pub fn new(sample: CodeSample) -> Self {
         Self {
-            code,
-            language,
+            code: sample.code,
+            language: sample.language,
+            problem: sample.problem,
+            source: sample.source,
             typed: Vec::new(),
             errors: 0,
+            whitespace: Whitespace::Exact,
+            skipped: 0,
+            after_skip: false,
             start_time: None,
-            last_typed_len: 0,
+            last_char_time: None,
+            scroll: 0,
         }
     }
 
-    pub fn run(&mut self, display: &Display, stats: &Stats) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn with_whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Run the challenge, keeping its keystrokes and bigrams under their own
+    /// `code` stats rather than the active natural language's.
+    pub fn run(
+        &mut self,
+        display: &Display,
+        stats: &mut Stats,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let language = stats.language().to_string();
+        stats.set_language(CODE_LANGUAGE);
+        let result = self.practice(display, stats);
+        stats.set_language(&language);
+        result
+    }
+
+    fn practice(
+        &mut self,
+        display: &Display,
+        stats: &mut Stats,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let ps = SyntaxSet::load_defaults_newlines();
         let ts = ThemeSet::load_defaults();
 
-        let syntax = ps.find_syntax_by_extension(&self.language)
+        let syntax = ps
+            .find_syntax_by_extension(&self.language)
             .unwrap_or_else(|| ps.find_syntax_plain_text());
-        let theme = &ts.themes["base16-ocean.dark"];
-
-        // Initial full render
-        self.render_full(syntax, theme, &ps, display)?;
-
-        loop {
-            match read_key(Duration::from_millis(50))? {
-                InputEvent::Char(ch) => {
-                    if self.start_time.is_none() {
-                        self.start_time = Some(Instant::now());
-                    }
-
-                    let code_chars: Vec<char> = self.code.chars().collect();
-                    if self.typed.len() < code_chars.len() {
-                        self.typed.push(ch);
-
-                        if ch != code_chars[self.typed.len() - 1] {
-                            self.errors += 1;
-                        }
-
-                        // Only update changed parts
-                        self.update_incremental(syntax, theme, &ps)?;
-
-                        // Check completion
-                        if self.typed.len() == code_chars.len() {
-                            self.show_summary(display, stats)?;
-                            break;
-                        }
+        let theme = display.theme();
+
+        // The target never changes, so highlight it once: one style per char
+        let mut styles = Vec::new();
+        match theme.syntax_theme {
+            Some(name) => {
+                let mut highlighter = HighlightLines::new(syntax, &ts.themes[name]);
+                for line in LinesWithEndings::from(&self.code) {
+                    for (style, text) in highlighter.highlight_line(line, &ps)? {
+                        let style = theme.text.fg(style_to_crossterm_color(style));
+                        styles.extend(text.chars().map(|_| style));
                     }
                 }
-                InputEvent::Enter => {
-                    if self.start_time.is_none() {
-                        self.start_time = Some(Instant::now());
-                    }
-
-                    let code_chars: Vec<char> = self.code.chars().collect();
-                    if self.typed.len() < code_chars.len() {
-                        self.typed.push('\n');
+            }
+            None => styles = vec![theme.text; self.code.chars().count()],
+        }
 
-                        if '\n' != code_chars[self.typed.len() - 1] {
-                            self.errors += 1;
-                        }
+        let code_chars: Vec<char> = self.code.chars().collect();
+        loop {
+            self.render(&styles, display)?;
 
-                        // Only update changed parts
-                        self.update_incremental(syntax, theme, &ps)?;
+            match read_key(Duration::from_millis(50))? {
+                // The code isn't on screen, so don't score blind typing
+                InputEvent::Char(_) | InputEvent::Enter | InputEvent::Tab
+                    if display.too_small() => {}
+                InputEvent::Char(ch) => self.record(ch, &code_chars, stats),
+                InputEvent::Enter => self.record('\n', &code_chars, stats),
+                InputEvent::Tab => self.record('\t', &code_chars, stats),
+                InputEvent::Escape => break,
+                InputEvent::Resize(width, height) => display.resize(width, height),
+                _ => {}
+            }
 
-                        // Check completion
-                        if self.typed.len() == code_chars.len() {
-                            self.show_summary(display, stats)?;
-                            break;
-                        }
-                    }
-                }
-                InputEvent::Tab => {
-                    if self.start_time.is_none() {
-                        self.start_time = Some(Instant::now());
-                    }
+            if self.typed.len() == code_chars.len() {
+                break;
+            }
+        }
 
-                    let code_chars: Vec<char> = self.code.chars().collect();
-                    if self.typed.len() < code_chars.len() {
-                        self.typed.push('\t');
+        // Nothing typed, nothing to score
+        if self.typed.is_empty() {
+            return Ok(());
+        }
+        self.show_summary(display, stats)
+    }
 
-                        if '\t' != code_chars[self.typed.len() - 1] {
-                            self.errors += 1;
-                        }
+    /// Score one typed character against the code and feed the key and
+    /// bigram stats, as `TypingSession` does for prose, then fill in any
+    /// whitespace the mode skips.
+    fn record(&mut self, ch: char, code_chars: &[char], stats: &mut Stats) {
+        let pos = self.typed.len();
+        if pos >= code_chars.len() {
+            return;
+        }
+        if self.start_time.is_none() {
+            self.start_time = Some(Instant::now());
+        }
 
-                        // Only update changed parts
-                        self.update_incremental(syntax, theme, &ps)?;
+        let expected = code_chars[pos];
+        let collapse = self.whitespace == Whitespace::Collapse
+            && expected.is_whitespace()
+            && ch.is_whitespace();
+        let correct = ch == expected || collapse;
+        if correct {
+            self.typed.push(expected);
+        } else {
+            self.typed.push(ch);
+            self.errors += 1;
+        }
 
-                        // Check completion
-                        if self.typed.len() == code_chars.len() {
-                            self.show_summary(display, stats)?;
-                            break;
-                        }
-                    }
+        // Enter and Tab count toward accuracy, but per-key stats are kept
+        // for printable keys only
+        if !expected.is_control() {
+            stats.record_keystroke(&expected.to_string(), correct);
+            let prev = pos.checked_sub(1).map(|i| code_chars[i]);
+            if let (Some(prev), Some(last)) = (prev, self.last_char_time) {
+                if !prev.is_control() && !self.after_skip {
+                    stats.record_bigram((&prev.to_string(), &expected.to_string()), last.elapsed());
                 }
-                InputEvent::Escape => break,
-                _ => {}
             }
         }
+        self.last_char_time = Some(Instant::now());
 
-        Ok(())
+        let rest = &code_chars[pos + 1..];
+        let skip = match self.whitespace {
+            Whitespace::AutoIndent if correct && expected == '\n' => {
+                rest.iter().take_while(|c| matches!(c, ' ' | '\t')).count()
+            }
+            Whitespace::Collapse if collapse => {
+                rest.iter().take_while(|c| c.is_whitespace()).count()
+            }
+            _ => 0,
+        };
+        self.typed.extend_from_slice(&rest[..skip]);
+        self.skipped += skip;
+        self.after_skip = skip > 0;
     }
 
-    fn render_full(
-        &self,
-        syntax: &syntect::parsing::SyntaxReference,
-        theme: &syntect::highlighting::Theme,
-        ps: &SyntaxSet,
+    /// Characters typed and scored, leaving out filled-in whitespace.
+    fn scored(&self) -> usize {
+        self.typed.len() - self.skipped
+    }
+
+    fn render(
+        &mut self,
+        styles: &[Style],
         display: &Display,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        display.clear()?;
-        let mut out = stdout();
+        if display.too_small() {
+            return display.render_too_small();
+        }
+
+        let mut frame = display.frame();
+        let theme = display.theme();
 
         // Calculate split position
-        let term_width = crossterm::terminal::size()?.0 as usize;
+        let term_width = display.width();
         let split_pos = term_width / 2;
 
-        // Title
-        out.queue(cursor::MoveTo(0, 0))?
-            .queue(Print("TypeHero - CodeJam"))?;
+        // Title and what the code does
+        frame
+            .style(theme.title)
+            .move_to(0, 0)
+            .print(format!("TypeHero - CodeJam - {}", self.heading()));
+        if let Some(problem) = &self.problem {
+            frame
+                .style(theme.text)
+                .move_to(0, 1)
+                .print(&problem.statement);
+        }
 
-        // Stats line
+        // Stats line, with how far through the code the cursor is
+        let code_chars: Vec<char> = self.code.chars().collect();
+        let total_lines = self.code.lines().count().max(1);
+        let (cursor_line, cursor_width) = self.typed_end();
         let elapsed = self.start_time.map(|t| t.elapsed().as_secs()).unwrap_or(0);
-        let accuracy = if self.typed.is_empty() {
+        let accuracy = if self.scored() == 0 {
             100.0
         } else {
-            ((self.typed.len() - self.errors) as f64 / self.typed.len() as f64) * 100.0
+            ((self.scored() - self.errors) as f64 / self.scored() as f64) * 100.0
         };
 
-        out.queue(cursor::MoveTo(0, 1))?
-            .queue(Print(format!("Time: {}s | Accuracy: {:.1}% | Errors: {}", elapsed, accuracy, self.errors)))?;
-
-        out.queue(cursor::MoveTo(0, 2))?
-            .queue(Print("─".repeat(term_width)))?;
-
-        // Left pane: Target code with syntax highlighting
-        out.queue(cursor::MoveTo(0, 3))?
-            .queue(Print("TARGET:"))?;
-
-        let mut highlighter = HighlightLines::new(syntax, theme);
-        let mut row = 4;
-
-        for line in LinesWithEndings::from(&self.code) {
-            if row >= crossterm::terminal::size()?.1 - 2 {
-                break;
-            }
-
-            out.queue(cursor::MoveTo(2, row))?;
-
-            let ranges = highlighter.highlight_line(line, ps)?;
-            for (style, text) in ranges {
-                let color = style_to_crossterm_color(style);
-                out.queue(SetForegroundColor(color))?
-                    .queue(Print(text))?;
-            }
-
-            row += 1;
+        frame.style(theme.accent).move_to(0, 2).print(format!(
+            "Time: {}s | Accuracy: {:.1}% | Errors: {} | Line {} of {} | {}%",
+            elapsed,
+            accuracy,
+            self.errors,
+            cursor_line + 1,
+            total_lines,
+            self.typed.len() * 100 / code_chars.len().max(1)
+        ));
+
+        frame
+            .style(theme.dim)
+            .move_to(0, 3)
+            .print("─".repeat(term_width as usize));
+
+        // Scroll both panes together to keep the cursor line in view, with
+        // a few lines of context either side
+        let rows = (display.height() - 1 - CODE_TOP) as usize;
+        let margin = SCROLL_MARGIN.min(rows.saturating_sub(1) / 2);
+        if cursor_line < self.scroll + margin {
+            self.scroll = cursor_line.saturating_sub(margin);
+        } else if cursor_line + margin >= self.scroll + rows {
+            self.scroll = cursor_line + margin + 1 - rows;
         }
+        // Mistyped Enters can put the cursor below the last line of code
+        let lines = total_lines.max(cursor_line + 1);
+        self.scroll = self.scroll.min(lines.saturating_sub(rows));
+        let view = Viewport {
+            first: self.scroll,
+            rows,
+            gutter: total_lines.to_string().len() as u16 + 2,
+            cursor_line,
+            theme,
+        };
 
-        // Right pane: What they're typing
-        out.queue(SetForegroundColor(Color::Reset))?;
-        out.queue(cursor::MoveTo(split_pos as u16, 3))?
-            .queue(Print("YOUR CODE:"))?;
-
-        let typed_str: String = self.typed.iter().collect();
-        let code_chars: Vec<char> = self.code.chars().collect();
-
-        row = 4;
-        let col = split_pos + 2;
-        let mut highlighter_typed = HighlightLines::new(syntax, theme);
-
-        for (i, line) in LinesWithEndings::from(&typed_str).enumerate() {
-            if row >= crossterm::terminal::size()?.1 - 2 {
-                break;
-            }
-
-            out.queue(cursor::MoveTo(col as u16, row))?;
-
-            let ranges = highlighter_typed.highlight_line(line, ps)?;
-            for (style, text) in ranges {
-                for ch in text.chars() {
-                    let idx = i;
-                    let correct = self.typed.get(idx) == code_chars.get(idx);
-
-                    let color = if correct {
-                        style_to_crossterm_color(style)
-                    } else {
-                        Color::Red
-                    };
+        // Left pane: Target code with syntax highlighting
+        frame
+            .style(theme.text)
+            .move_to(0, CODE_TOP - 1)
+            .print("TARGET:");
+        view.draw(
+            &mut frame,
+            0,
+            split_pos.saturating_sub(1),
+            total_lines,
+            code_chars.iter().zip(styles.iter().copied()),
+        );
+
+        // Right pane: What they're typing, marked where it differs
+        frame
+            .style(theme.text)
+            .move_to(split_pos, CODE_TOP - 1)
+            .print("YOUR CODE:");
+        let typed = self.typed.iter().enumerate().map(|(i, ch)| {
+            let style = match code_chars.get(i) {
+                Some(expected) if expected == ch => styles[i],
+                _ => theme.incorrect,
+            };
+            (ch, style)
+        });
+        view.draw(
+            &mut frame,
+            split_pos,
+            term_width - split_pos,
+            cursor_line + 1,
+            typed,
+        );
+
+        // Cursor position, kept inside the pane when the line is clipped
+        let col = (split_pos + view.gutter + cursor_width as u16).min(term_width - 1);
+        frame.set_cursor(col, CODE_TOP + (cursor_line - view.first) as u16);
+
+        frame
+            .style(theme.dim)
+            .move_to(0, display.height() - 1)
+            .print("Press ESC to quit");
+
+        display.present(frame)
+    }
 
-                    out.queue(SetForegroundColor(color))?
-                        .queue(Print(ch))?;
+    /// "Problem 12 - easy - Python" (with "- part 1 of 2" for a long one),
+    /// "src/main.rs:40 - Rust" for code from a repository, or just the
+    /// language otherwise.
+    fn heading(&self) -> String {
+        let language = code_language_name(&self.language);
+        match (&self.problem, &self.source) {
+            (Some(problem), source) => {
+                let mut heading = format!(
+                    "Problem {} - {} - {}",
+                    problem.id, problem.difficulty, language
+                );
+                if let Some(source) = source {
+                    heading.push_str(&format!(" - {}", source));
                 }
+                heading
             }
-
-            row += 1;
+            (None, Some(source)) => format!("{} - {}", source, language),
+            (None, None) => language.to_string(),
         }
-
-        // Cursor position
-        out.queue(cursor::MoveTo(col as u16 + (self.typed.len() % 80) as u16, row))?;
-
-        out.queue(SetForegroundColor(Color::Reset))?;
-        out.queue(cursor::MoveTo(0, crossterm::terminal::size()?.1 - 1))?
-            .queue(Print("Press ESC to quit"))?;
-
-        out.flush()?;
-        Ok(())
     }
 
-    fn update_incremental(
-        &mut self,
-        _syntax: &syntect::parsing::SyntaxReference,
-        _theme: &syntect::highlighting::Theme,
-        _ps: &SyntaxSet,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        let term_width = crossterm::terminal::size()?.0 as usize;
+    /// Line index and display column where the next typed character goes.
+    fn typed_end(&self) -> (usize, usize) {
+        let typed: String = self.typed.iter().collect();
+        line_end(&typed)
+    }
 
-        // Update stats line only
-        let elapsed = self.start_time.map(|t| t.elapsed().as_secs()).unwrap_or(0);
-        let accuracy = if self.typed.is_empty() {
+    fn summary(&self) -> SessionSummary {
+        let duration_secs = self
+            .start_time
+            .map(|t| t.elapsed().as_secs_f64())
+            .unwrap_or(0.0);
+        let total_chars = self.scored();
+        let raw_wpm = if duration_secs > 0.0 {
+            (total_chars as f64 / 5.0) / (duration_secs / 60.0)
+        } else {
+            0.0
+        };
+        let accuracy = if total_chars == 0 {
             100.0
         } else {
-            ((self.typed.len() - self.errors) as f64 / self.typed.len() as f64) * 100.0
+            ((total_chars - self.errors) as f64 / total_chars as f64) * 100.0
         };
 
-        out.queue(cursor::MoveTo(0, 1))?
-            .queue(Print(format!(
-                "Time: {}s | Accuracy: {:.1}% | Errors: {}{}",
-                elapsed,
-                accuracy,
-                self.errors,
-                " ".repeat(term_width.saturating_sub(50))
-            )))?;
+        SessionSummary {
+            timestamp: Utc::now(),
+            duration_secs,
+            total_chars,
+            errors: self.errors,
+            raw_wpm,
+            accuracy,
+            adjusted_wpm: raw_wpm * (accuracy / 100.0).powi(2),
+            modifiers: String::new(),
+            mode: "codejam".to_string(),
+        }
+    }
 
-        // Update only the new character in right pane
-        let split_pos = term_width / 2;
-        let code_chars: Vec<char> = self.code.chars().collect();
+    /// Store the run as a CodeJam session and show the results screen.
+    fn show_summary(
+        &self,
+        display: &Display,
+        stats: &mut Stats,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let summary = self.summary();
+        let _ = stats.add_session(summary.clone());
+        stats.save()?;
+
+        let code_chars = self.code.chars().count();
+        let mut notes = vec![format!("CodeJam - {}", self.heading())];
+        if let Some(problem) = &self.problem {
+            notes.push(problem.statement.clone());
+        }
+        notes.push(format!(
+            "{} of {} characters, {} lines",
+            self.typed.len(),
+            code_chars,
+            self.code.lines().count()
+        ));
+        if self.typed.len() < code_chars {
+            notes.push("Quit before the end of the code.".to_string());
+        }
+        if self.skipped > 0 {
+            notes.push(format!(
+                "{} whitespace characters filled in ({}), not counted",
+                self.skipped,
+                self.whitespace.name()
+            ));
+        }
+        show_summary_with_notes(display, &summary, stats, &notes)
+    }
+}
 
-        if !self.typed.is_empty() {
-            let idx = self.typed.len() - 1;
-            let ch = self.typed[idx];
-            let correct = self.typed.get(idx) == code_chars.get(idx);
+/// Line index and display width of the last line of `text`.
+fn line_end(text: &str) -> (usize, usize) {
+    let line = text.matches('\n').count();
+    let last = text.rsplit('\n').next().unwrap_or("");
+    (line, text_width(last))
+}
 
-            // Calculate position for new character
-            let typed_str: String = self.typed.iter().take(idx + 1).collect();
-            let lines: Vec<&str> = typed_str.lines().collect();
-            let row = 4 + lines.len().saturating_sub(1);
-            let col = split_pos + 2 + lines.last().map(|l| l.len()).unwrap_or(0).saturating_sub(1);
+/// The lines of code on screen, shared by both panes.
+struct Viewport {
+    first: usize,
+    rows: usize,
+    /// Columns for the line numbers and the space after them
+    gutter: u16,
+    cursor_line: usize,
+    theme: &'static Theme,
+}
 
-            let color = if correct {
-                Color::Green
+impl Viewport {
+    /// Draw the visible part of `lines` lines of styled code into a pane
+    /// `width` columns wide, numbering the lines and clipping anything too
+    /// wide for it.
+    fn draw<'a>(
+        &self,
+        frame: &mut Frame,
+        col: u16,
+        width: u16,
+        lines: usize,
+        chars: impl Iterator<Item = (&'a char, Style)>,
+    ) {
+        let theme = self.theme;
+        let last = lines.min(self.first + self.rows);
+        for (row, line) in (self.first..last).enumerate() {
+            let style = if line == self.cursor_line {
+                theme.accent
             } else {
-                Color::Red
+                theme.dim
             };
-
-            out.queue(cursor::MoveTo(col as u16, row as u16))?
-                .queue(SetForegroundColor(color))?
-                .queue(Print(ch))?;
-
-            self.last_typed_len = self.typed.len();
+            frame
+                .style(style)
+                .move_to(col, CODE_TOP + row as u16)
+                .print(format!("{:>1$}", line + 1, self.gutter as usize - 1));
         }
 
-        out.queue(SetForegroundColor(Color::Reset))?;
-        out.flush()?;
-        Ok(())
-    }
-
-    fn show_summary(&self, _display: &Display, _stats: &Stats) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Show completion summary
-        Ok(())
+        let code_col = col + self.gutter;
+        let code_width = width.saturating_sub(self.gutter) as usize;
+        let mut line = 0;
+        let mut x = 0;
+        frame.move_to(code_col, CODE_TOP);
+        for (ch, style) in chars {
+            if *ch == '\n' {
+                line += 1;
+                x = 0;
+                if line >= last {
+                    break;
+                }
+                if line >= self.first {
+                    frame.move_to(code_col, CODE_TOP + (line - self.first) as u16);
+                }
+                continue;
+            }
+            let ch_width = text_width(ch.encode_utf8(&mut [0; 4]));
+            if line < self.first || x + ch_width > code_width {
+                continue;
+            }
+            frame.style(style).print(ch);
+            x += ch_width;
+        }
     }
 }
 
-fn style_to_crossterm_color(style: Style) -> Color {
+fn style_to_crossterm_color(style: highlighting::Style) -> Color {
     Color::Rgb {
         r: style.foreground.r,
         g: style.foreground.g,
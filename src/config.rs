@@ -0,0 +1,169 @@
+use crate::code_challenge::Whitespace;
+use crate::custom_text::{self, TextOptions};
+use crate::exercises::CodeFilter;
+use crate::theme::{Theme, THEMES};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+// Accepted values, checked on load and enforced by the Settings screen
+pub const WORDS: RangeInclusive<u32> = 10..=500;
+pub const QUICK_DRILL_WORDS: RangeInclusive<u32> = 5..=200;
+pub const MASTERY_ACCURACY: RangeInclusive<u32> = 50..=100;
+pub const MASTERY_ATTEMPTS: RangeInclusive<u32> = 1..=10;
+pub const COUNTDOWN_MS: RangeInclusive<u32> = 0..=3000;
+pub const EARLY_QUIT_WARNING: RangeInclusive<u32> = 0..=100;
+pub const CHUNK_CHARS: RangeInclusive<usize> = 50..=5000;
+
+/// Settings read from `~/.config/typehero/config.toml`. Every key is
+/// optional; a missing file means all defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: String,
+    /// Words or patterns in Random Words, Code Patterns, Targeted, Adaptive
+    /// and Generated Prose sessions
+    pub words: u32,
+    pub quick_drill_words: u32,
+    /// Guided Practice: accuracy (%) every mastery attempt must reach...
+    pub mastery_accuracy: u32,
+    /// ...over this many consecutive attempts
+    pub mastery_attempts: u32,
+    /// Length of each step of the 3-2-1 countdown before a guided attempt
+    pub countdown_ms: u32,
+    /// Quitting before this much (%) of the text is typed asks for
+    /// confirmation
+    pub early_quit_warning: u32,
+    /// How CodeJam treats indentation and other whitespace
+    pub code_whitespace: Whitespace,
+    /// File the Custom Text menu entry practices on, instead of
+    /// `~/.typehero/custom.txt`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_text_path: Option<PathBuf>,
+    /// How the Custom Text menu entry prepares that file, as a
+    /// `[custom_text]` table
+    pub custom_text: TextOptions,
+    /// Languages and difficulties for CodeJam and Code One-Liners, as a
+    /// `[code]` table
+    pub code: CodeFilter,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: "default".to_string(),
+            words: 50,
+            quick_drill_words: 20,
+            mastery_accuracy: 95,
+            mastery_attempts: 3,
+            countdown_ms: 800,
+            early_quit_warning: 50,
+            code_whitespace: Whitespace::Exact,
+            custom_text_path: None,
+            custom_text: TextOptions::default(),
+            code: CodeFilter::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::home_dir()?.join(".config/typehero/config.toml"))
+    }
+
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e).into()),
+        };
+
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| format!("Invalid config in {}: {}", path.display(), e))?;
+        config
+            .validate()
+            .map_err(|e| format!("Invalid config in {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not find your home directory")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml::to_string(self)?)
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if Theme::find(&self.theme).is_none() {
+            let names: Vec<&str> = THEMES.iter().map(|t| t.name).collect();
+            return Err(format!(
+                "unknown theme '{}'. Available: {}",
+                self.theme,
+                names.join(", ")
+            ));
+        }
+        check("words", self.words, WORDS)?;
+        check(
+            "quick_drill_words",
+            self.quick_drill_words,
+            QUICK_DRILL_WORDS,
+        )?;
+        check("mastery_accuracy", self.mastery_accuracy, MASTERY_ACCURACY)?;
+        check("mastery_attempts", self.mastery_attempts, MASTERY_ATTEMPTS)?;
+        check("countdown_ms", self.countdown_ms, COUNTDOWN_MS)?;
+        check(
+            "early_quit_warning",
+            self.early_quit_warning,
+            EARLY_QUIT_WARNING,
+        )?;
+        check(
+            "custom_text.chunk_chars",
+            self.custom_text.chunk_chars,
+            CHUNK_CHARS,
+        )?;
+        self.code.validate()
+    }
+
+    /// The Custom Text file, with a leading `~` meaning the home directory.
+    pub fn custom_text_path(&self) -> Option<PathBuf> {
+        let Some(path) = &self.custom_text_path else {
+            return custom_text::default_path();
+        };
+        match path.strip_prefix("~") {
+            Ok(rest) => Some(dirs::home_dir()?.join(rest)),
+            Err(_) => Some(path.clone()),
+        }
+    }
+
+    /// The configured theme, or no-color when `NO_COLOR` is set.
+    pub fn theme(&self) -> &'static Theme {
+        Theme::resolve(&self.theme).unwrap_or(&THEMES[0])
+    }
+}
+
+fn check<T: PartialOrd + fmt::Display>(
+    key: &str,
+    value: T,
+    range: RangeInclusive<T>,
+) -> Result<(), String> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} must be between {} and {} (got {})",
+            key,
+            range.start(),
+            range.end(),
+            value
+        ))
+    }
+}
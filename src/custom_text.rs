@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const DEFAULT_CHUNK_CHARS: usize = 400;
+
+pub enum TextInput {
+    File(PathBuf),
+    Stdin,
+    Clipboard,
+}
+
+/// How a document is turned into practice chunks. Also the `[custom_text]`
+/// config table, used by the Custom Text menu entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TextOptions {
+    /// Replace typographic punctuation with ASCII and drop anything else
+    /// that can't be typed on a US keyboard.
+    pub keyboard_only: bool,
+    /// Rough target size of each practice chunk, in characters.
+    pub chunk_chars: usize,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            keyboard_only: false,
+            chunk_chars: DEFAULT_CHUNK_CHARS,
+        }
+    }
+}
+
+/// `~/.typehero/custom.txt`, used by the Custom Text menu entry unless the
+/// config names another file.
+pub fn default_path() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".typehero/custom.txt"))
+}
+
+pub fn read_input(input: &TextInput) -> Result<String, Box<dyn std::error::Error>> {
+    match input {
+        TextInput::File(path) => read_file(path),
+        TextInput::Stdin => {
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)?;
+            Ok(content)
+        }
+        TextInput::Clipboard => read_clipboard(),
+    }
+}
+
+pub fn read_file(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e).into())
+}
+
+fn read_clipboard() -> Result<String, Box<dyn std::error::Error>> {
+    // Whichever clipboard tool the platform has installed
+    let candidates: &[(&str, &[&str])] = &[
+        ("pbpaste", &[]),
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-selection", "clipboard", "-o"]),
+        ("xsel", &["--clipboard", "--output"]),
+        ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+    ];
+
+    for (program, args) in candidates {
+        if let Ok(output) = Command::new(program).args(*args).output() {
+            if output.status.success() {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+        }
+    }
+
+    Err("Could not read the clipboard (tried pbpaste, wl-paste, xclip, xsel, powershell)".into())
+}
+
+/// Turn a document into typeable practice chunks: paragraphs are kept
+/// together where possible, whitespace is collapsed, and long paragraphs are
+/// split at sentence (then word) boundaries.
+pub fn prepare(raw: &str, options: &TextOptions) -> Vec<String> {
+    let limit = options.chunk_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in paragraphs(raw) {
+        let paragraph = if options.keyboard_only {
+            to_keyboard_chars(&paragraph)
+        } else {
+            paragraph
+        };
+        let paragraph = collapse_whitespace(&paragraph);
+        if paragraph.is_empty() {
+            continue;
+        }
+
+        for piece in split_long(&paragraph, limit) {
+            if !current.is_empty() && current.len() + 1 + piece.len() > limit {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn paragraphs(raw: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+
+    for line in raw.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+        } else {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(line);
+        }
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Replace typographic quotes, dashes, ellipses and spaces with their ASCII
+/// forms. Letters, accented or not, are kept.
+pub fn plain_punctuation(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' => out.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => out.push('"'),
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => out.push('-'),
+            '\u{2026}' => out.push_str("..."),
+            '\u{00A0}' | '\u{2000}'..='\u{200A}' | '\t' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn to_keyboard_chars(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in plain_punctuation(text).chars() {
+        match ch {
+            '\u{2022}' | '\u{00B7}' => out.push('-'),
+            c if c.is_ascii_graphic() || c == ' ' => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn split_long(paragraph: &str, limit: usize) -> Vec<String> {
+    if paragraph.len() <= limit {
+        return vec![paragraph.to_string()];
+    }
+
+    // Sentences first, then fall back to words for run-on sentences
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for sentence in sentences(paragraph) {
+        let units: Vec<&str> = if sentence.len() > limit {
+            sentence.split(' ').collect()
+        } else {
+            vec![sentence]
+        };
+
+        for unit in units {
+            if !current.is_empty() && current.len() + 1 + unit.len() > limit {
+                pieces.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(unit);
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn sentences(paragraph: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+
+    let bytes = paragraph.as_bytes();
+    for i in 0..bytes.len().saturating_sub(1) {
+        if matches!(bytes[i], b'.' | b'?' | b'!') && bytes[i + 1] == b' ' {
+            sentences.push(&paragraph[start..=i]);
+            start = i + 2;
+        }
+    }
+    if start < paragraph.len() {
+        sentences.push(&paragraph[start..]);
+    }
+    sentences
+}
@@ -0,0 +1,120 @@
+use flate2::read::GzDecoder;
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::OnceLock;
+
+// ~49,500 English words, most common first, one per line: ranked by
+// frequency in public-domain novels, then in English software documentation,
+// then rarer dictionary forms by how common their root word is
+const ENGLISH_WORDS_GZ: &[u8] = include_bytes!("../data/english-words.txt.gz");
+
+// Longest n-gram indexed; targeted items are keys (1) and bigrams (2)
+const MAX_NGRAM: usize = 3;
+
+/// A frequency-ranked word list indexed by the n-grams each word contains,
+/// used to build targeted drills out of real words.
+pub struct Dictionary {
+    words: Vec<String>,
+    // Language packs keep nouns capitalised (German), the index is lowercase
+    lowercase: Vec<String>,
+    // Each gram's words with their drill weights, in rank order
+    index: HashMap<String, Vec<(usize, f64)>>,
+}
+
+impl Dictionary {
+    pub fn english() -> &'static Dictionary {
+        static ENGLISH: OnceLock<Dictionary> = OnceLock::new();
+        ENGLISH.get_or_init(|| {
+            let mut content = String::new();
+            let _ = GzDecoder::new(ENGLISH_WORDS_GZ).read_to_string(&mut content);
+            Dictionary::from_words(content.lines().map(|l| l.trim().to_string()))
+        })
+    }
+
+    /// Build a dictionary from words in rank order (most frequent first).
+    pub fn from_words(words: impl IntoIterator<Item = String>) -> Self {
+        let words: Vec<String> = words.into_iter().filter(|w| !w.is_empty()).collect();
+        let lowercase: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+        let mut index: HashMap<String, Vec<(usize, f64)>> = HashMap::new();
+
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for (rank, word) in lowercase.iter().enumerate() {
+            let chars: Vec<char> = word.chars().collect();
+            counts.clear();
+            for n in 1..=MAX_NGRAM {
+                for gram in chars.windows(n) {
+                    let gram: String = gram.iter().collect();
+                    match counts.iter_mut().find(|(g, _)| *g == gram) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((gram, 1)),
+                    }
+                }
+            }
+            for (gram, count) in counts.drain(..) {
+                let weight = weight(rank, gram.chars().count(), count, chars.len());
+                index.entry(gram).or_default().push((rank, weight));
+            }
+        }
+
+        Self {
+            words,
+            lowercase,
+            index,
+        }
+    }
+
+    /// Pick a real word that exercises `pattern`, weighted by how common the
+    /// word is and how densely it contains the pattern.
+    ///
+    /// Non-letter edges of the pattern are kept at the matching end of the
+    /// word: `"e "` picks a word ending in "e", `"(t"` yields e.g. `"(the"`.
+    pub fn targeted_word<R: Rng>(&self, pattern: &str, rng: &mut R) -> Option<String> {
+        let core = pattern.trim_matches(|c: char| !c.is_alphabetic());
+        if core.is_empty() || core.chars().count() > MAX_NGRAM {
+            return None;
+        }
+
+        let start = pattern.find(core)?;
+        let lead = &pattern[..start];
+        let trail = &pattern[start + core.len()..];
+        let key = core.to_lowercase();
+        // A capitalised pattern like "Th" only occurs at the start of a word
+        let capitalised = core.chars().next().is_some_and(|c| c.is_uppercase());
+
+        let candidates: Vec<&(usize, f64)> = self
+            .index
+            .get(&key)?
+            .iter()
+            .filter(|(i, _)| {
+                (lead.is_empty() && !capitalised) || self.lowercase[*i].starts_with(&key)
+            })
+            .filter(|(i, _)| trail.is_empty() || self.lowercase[*i].ends_with(&key))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let dist = WeightedIndex::new(candidates.iter().map(|(_, weight)| weight)).ok()?;
+        let mut word = self.words[candidates[dist.sample(rng)].0].clone();
+
+        if capitalised {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                word = first.to_uppercase().chain(chars).collect();
+            }
+        }
+
+        Some(format!("{}{}{}", lead.trim(), word, trail.trim()))
+    }
+}
+
+// Words are stored in rank order, so the index doubles as frequency rank.
+// Density is the share of the word the gram's occurrences cover.
+fn weight(rank: usize, gram_len: usize, occurrences: usize, word_len: usize) -> f64 {
+    let frequency = 1.0 / (1.0 + rank as f64 / 200.0).powi(2);
+    let density = (occurrences * gram_len) as f64 / word_len.max(1) as f64;
+    frequency * density
+}
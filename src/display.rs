@@ -1,35 +1,102 @@
-use crossterm::{
-    cursor,
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
-    terminal::{self, ClearType},
-    QueueableCommand,
-};
-use std::io::{stdout, Write};
+use crate::render::{Frame, Renderer};
+use crate::stats::SessionSummary;
+use crate::theme::Theme;
+use crossterm::terminal;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Smallest terminal the screens are laid out for. Below this every screen
+/// shows a prompt to enlarge the window instead.
+pub const MIN_WIDTH: u16 = 80;
+pub const MIN_HEIGHT: u16 = 24;
 
 pub struct Display {
-    width: u16,
-    #[allow(dead_code)]
-    height: u16,
+    // Cells so screens holding `&Display` can apply resize events
+    width: Cell<u16>,
+    height: Cell<u16>,
+    theme: Cell<&'static Theme>,
+    renderer: RefCell<Renderer>,
 }
 
 impl Display {
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(theme: &'static Theme) -> Result<Self, Box<dyn std::error::Error>> {
         let (width, height) = terminal::size()?;
-        Ok(Self { width, height })
+        Ok(Self {
+            width: Cell::new(width),
+            height: Cell::new(height),
+            theme: Cell::new(theme),
+            renderer: RefCell::new(Renderer::default()),
+        })
     }
 
-    pub fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
-        stdout()
-            .queue(terminal::Clear(ClearType::All))?
-            .queue(cursor::MoveTo(0, 0))?
-            .flush()?;
-        Ok(())
+    pub fn width(&self) -> u16 {
+        self.width.get()
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height.get()
+    }
+
+    /// Record the new terminal size from an `InputEvent::Resize`. Screens
+    /// lay themselves out again on their next render.
+    pub fn resize(&self, width: u16, height: u16) {
+        self.width.set(width);
+        self.height.set(height);
+        self.renderer.borrow_mut().invalidate();
+    }
+
+    pub fn theme(&self) -> &'static Theme {
+        self.theme.get()
+    }
+
+    pub fn set_theme(&self, theme: &'static Theme) {
+        self.theme.set(theme);
+    }
+
+    pub fn too_small(&self) -> bool {
+        self.width() < MIN_WIDTH || self.height() < MIN_HEIGHT
+    }
+
+    /// Shown in place of any screen while the terminal is below the minimum
+    /// size.
+    pub fn render_too_small(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut frame = self.frame();
+        let theme = self.theme();
+
+        let lines = [
+            "Terminal too small".to_string(),
+            format!("{} x {}", self.width(), self.height()),
+            format!("Need at least {} x {}", MIN_WIDTH, MIN_HEIGHT),
+        ];
+        let top = (self.height() / 2).saturating_sub(1);
+        for (i, line) in lines.iter().enumerate() {
+            let col = (self.width() as usize).saturating_sub(text_width(line)) / 2;
+            frame
+                .move_to(col as u16, top + i as u16)
+                .style(if i == 0 { theme.accent } else { theme.dim })
+                .print(line);
+        }
+        frame.reset();
+
+        self.present(frame)
+    }
+
+    /// A blank frame the size of the terminal, for a screen to draw into.
+    pub fn frame(&self) -> Frame {
+        Frame::new(self.width(), self.height())
+    }
+
+    /// Draw a finished frame, sending only what changed since the last one.
+    pub fn present(&self, frame: Frame) -> Result<(), Box<dyn std::error::Error>> {
+        self.renderer.borrow_mut().draw(frame)
     }
 
     pub fn render_test(
         &self,
         text: &str,
-        typed: &[(char, bool)],
+        typed: &[(String, bool)],
         current_pos: usize,
         elapsed_secs: f64,
         wpm: f64,
@@ -37,184 +104,216 @@ impl Display {
         adjusted_wpm: f64,
         quit_warning_shown: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        self.clear()?;
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let mut frame = self.frame();
+        let theme = self.theme();
 
         // Title
-        out.queue(cursor::MoveTo(0, 0))?
-            .queue(SetForegroundColor(Color::Cyan))?
-            .queue(Print("═".repeat(self.width as usize)))?
-            .queue(cursor::MoveTo(0, 1))?
-            .queue(Print("  TypeHero - Accuracy-First Typing Trainer"))?
-            .queue(cursor::MoveTo(0, 2))?
-            .queue(Print("═".repeat(self.width as usize)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(0, 0)
+            .style(theme.title)
+            .print("═".repeat(self.width() as usize))
+            .move_to(0, 1)
+            .print("  TypeHero - Accuracy-First Typing Trainer")
+            .move_to(0, 2)
+            .print("═".repeat(self.width() as usize))
+            .reset();
 
         // Stats panel
-        out.queue(cursor::MoveTo(0, 4))?
-            .queue(SetForegroundColor(Color::Yellow))?
-            .queue(Print(format!("Time: {:.1}s", elapsed_secs)))?
-            .queue(cursor::MoveTo(20, 4))?
-            .queue(Print(format!("Raw WPM: {:.1}", wpm)))?
-            .queue(cursor::MoveTo(40, 4))?
-            .queue(SetForegroundColor(if accuracy >= 95.0 {
-                Color::Green
-            } else if accuracy >= 85.0 {
-                Color::Yellow
-            } else {
-                Color::Red
-            }))?
-            .queue(Print(format!("Accuracy: {:.1}%", accuracy)))?
-            .queue(cursor::MoveTo(60, 4))?
-            .queue(SetForegroundColor(Color::Green))?
-            .queue(Print(format!("Adj WPM: {:.1}", adjusted_wpm)))?
-            .queue(ResetColor)?;
-
-        // Text display area
-        let start_row = 7;
-        let _max_width = (self.width - 4) as usize;
-
-        // Render the original text with current character highlighted
-        out.queue(cursor::MoveTo(2, start_row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?;
-
-        for (i, ch) in text.chars().enumerate() {
-            if i == current_pos {
-                out.queue(SetBackgroundColor(Color::DarkBlue))?
-                    .queue(SetForegroundColor(Color::White))?
-                    .queue(Print(ch))?
-                    .queue(ResetColor)?
-                    .queue(SetForegroundColor(Color::DarkGrey))?;
-            } else {
-                out.queue(Print(ch))?;
+        frame
+            .move_to(0, 4)
+            .style(theme.accent)
+            .print(format!("Time: {:.1}s", elapsed_secs))
+            .move_to(20, 4)
+            .print(format!("Raw WPM: {:.1}", wpm))
+            .move_to(40, 4)
+            .style(theme.accuracy(accuracy))
+            .print(format!("Accuracy: {:.1}%", accuracy))
+            .move_to(60, 4)
+            .style(theme.good)
+            .print(format!("Adj WPM: {:.1}", adjusted_wpm))
+            .reset();
+
+        // Text display area: the instructions sit at the bottom and the
+        // wrapped text scrolls in the rows between
+        let start_row: u16 = 7;
+        let instr_row = self.height().saturating_sub(4).max(start_row + 2);
+        let max_width = (self.width() as usize).saturating_sub(4).max(1);
+        let visible_rows = (instr_row - start_row - 1) as usize;
+
+        let graphemes: Vec<&str> = text.graphemes(true).collect();
+        let lines = wrap_graphemes(&graphemes, max_width);
+
+        // Keep one already-typed line above the current one once there's
+        // enough text to scroll
+        let current_line = lines
+            .iter()
+            .position(|line| current_pos < line.end)
+            .unwrap_or(lines.len().saturating_sub(1));
+        let first_line = current_line
+            .saturating_sub(1)
+            .min(lines.len().saturating_sub(visible_rows));
+
+        for (row, line) in lines.iter().skip(first_line).take(visible_rows).enumerate() {
+            let mut col = 2;
+            for i in line.clone() {
+                let target = graphemes[i];
+                let width = grapheme_width(target);
+                frame.move_to(col as u16, start_row + row as u16);
+                col += width;
+
+                match typed.get(i) {
+                    Some((_, true)) => {
+                        frame.style(theme.correct).print(target);
+                    }
+                    Some((ch, false)) => {
+                        // Show what was typed when it fits the target's cell,
+                        // otherwise the target itself
+                        let shown = if grapheme_width(ch) == width {
+                            ch
+                        } else {
+                            target
+                        };
+                        frame.style(theme.incorrect).print(shown).reset();
+                    }
+                    None if i == current_pos => {
+                        frame.style(theme.selected).print(target).reset();
+                    }
+                    None => {
+                        frame.style(theme.dim).print(target);
+                    }
+                }
             }
         }
-        out.queue(ResetColor)?;
-
-        // Render typed text with mistakes highlighted
-        out.queue(cursor::MoveTo(2, start_row + 5))?;
-
-        for (ch, correct) in typed {
-            if *correct {
-                out.queue(SetForegroundColor(Color::Green))?
-                    .queue(Print(ch))?;
-            } else {
-                out.queue(SetForegroundColor(Color::Red))?
-                    .queue(SetBackgroundColor(Color::DarkRed))?
-                    .queue(Print(ch))?
-                    .queue(ResetColor)?;
-            }
-        }
-        out.queue(ResetColor)?;
+        frame.reset();
 
         // Instructions or warning
-        let instr_row = start_row + 9;
         if quit_warning_shown {
-            out.queue(cursor::MoveTo(2, instr_row))?
-                .queue(SetForegroundColor(Color::Yellow))?
-                .queue(Print("The point isn't perfection on every run."))?;
-            out.queue(cursor::MoveTo(2, instr_row + 1))?
-                .queue(Print("Keep going through the discomfort - that's where growth happens."))?;
-            out.queue(cursor::MoveTo(2, instr_row + 2))?
-                .queue(SetForegroundColor(Color::DarkGrey))?
-                .queue(Print("Press ESC again to quit anyway"))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, instr_row)
+                .style(theme.accent)
+                .print("The point isn't perfection on every run.");
+            frame
+                .move_to(2, instr_row + 1)
+                .print("Keep going through the discomfort - that's where growth happens.");
+            frame
+                .move_to(2, instr_row + 2)
+                .style(theme.dim)
+                .print("Press ESC again to quit anyway")
+                .reset();
         } else {
-            out.queue(cursor::MoveTo(2, instr_row))?
-                .queue(SetForegroundColor(Color::DarkGrey))?
-                .queue(Print("Press ESC to quit anytime"))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, instr_row)
+                .style(theme.dim)
+                .print("Press ESC to quit anytime")
+                .reset();
         }
 
-        out.flush()?;
-        Ok(())
+        self.present(frame)
     }
 
     pub fn render_summary(
         &self,
-        elapsed_secs: f64,
-        total_chars: usize,
-        errors: usize,
-        raw_wpm: f64,
-        accuracy: f64,
-        adjusted_wpm: f64,
+        summary: &SessionSummary,
         slowest_bigrams: &[(String, f64)],
-        error_prone_keys: &[(char, f64)],
+        error_prone_keys: &[(String, f64)],
+        notes: &[String],
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        self.clear()?;
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let accuracy = summary.accuracy;
+        let mut frame = self.frame();
+        let theme = self.theme();
 
         let mut row = 0;
 
         // Title
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(SetForegroundColor(Color::Cyan))?
-            .queue(Print("═".repeat(self.width as usize)))?;
+        frame
+            .move_to(0, row)
+            .style(theme.title)
+            .print("═".repeat(self.width() as usize));
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print("  Session Summary"))?;
+        frame.move_to(0, row).print("  Session Summary");
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print("═".repeat(self.width as usize)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(0, row)
+            .print("═".repeat(self.width() as usize))
+            .reset();
         row += 2;
 
         // Overall stats
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print(format!("Duration: {:.1}s", elapsed_secs)))?;
+        frame
+            .move_to(2, row)
+            .print(format!("Duration: {:.1}s", summary.duration_secs));
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print(format!("Characters: {}", total_chars)))?;
+        frame
+            .move_to(2, row)
+            .print(format!("Characters: {}", summary.total_chars));
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print(format!("Errors: {}", errors)))?;
+        frame
+            .move_to(2, row)
+            .print(format!("Errors: {}", summary.errors));
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print(format!("Raw WPM: {:.1}", raw_wpm)))?;
+        frame
+            .move_to(2, row)
+            .print(format!("Raw WPM: {:.1}", summary.raw_wpm));
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(if accuracy >= 95.0 {
-                Color::Green
-            } else if accuracy >= 85.0 {
-                Color::Yellow
-            } else {
-                Color::Red
-            }))?
-            .queue(Print(format!("Accuracy: {:.1}%", accuracy)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.accuracy(accuracy))
+            .print(format!("Accuracy: {:.1}%", accuracy))
+            .reset();
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::Green))?
-            .queue(Print(format!("Adjusted WPM: {:.1}", adjusted_wpm)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.good)
+            .print(format!("Adjusted WPM: {:.1}", summary.adjusted_wpm))
+            .reset();
         row += 2;
 
         // Verdict
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(if accuracy >= 95.0 {
-                Color::Green
+        frame
+            .move_to(2, row)
+            .style(if accuracy >= 95.0 {
+                theme.good
             } else {
-                Color::Red
-            }))?
-            .queue(Print(if accuracy >= 95.0 {
+                theme.bad
+            })
+            .print(if accuracy >= 95.0 {
                 "✓ PASSED - Great accuracy!"
             } else {
                 "✗ FAILED - Need 95%+ accuracy"
-            }))?
-            .queue(ResetColor)?;
+            })
+            .reset();
         row += 2;
 
+        // Mode-specific extras (attribution, personal bests, ...)
+        if !notes.is_empty() {
+            for note in notes {
+                frame.move_to(2, row).print(note);
+                row += 1;
+            }
+            row += 1;
+        }
+
         // Slowest bigrams
         if !slowest_bigrams.is_empty() {
-            out.queue(cursor::MoveTo(2, row))?
-                .queue(SetForegroundColor(Color::Yellow))?
-                .queue(Print("Slowest bigrams (need practice):"))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, row)
+                .style(theme.accent)
+                .print("Slowest bigrams (need practice):")
+                .reset();
             row += 1;
 
             for (bigram, ms) in slowest_bigrams {
-                out.queue(cursor::MoveTo(4, row))?
-                    .queue(Print(format!("  '{}' - {:.0}ms", bigram, ms)))?;
+                frame
+                    .move_to(4, row)
+                    .print(format!("  '{}' - {:.0}ms", bigram, ms));
                 row += 1;
             }
             row += 1;
@@ -222,77 +321,154 @@ impl Display {
 
         // Error-prone keys
         if !error_prone_keys.is_empty() {
-            out.queue(cursor::MoveTo(2, row))?
-                .queue(SetForegroundColor(Color::Red))?
-                .queue(Print("Most error-prone keys:"))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, row)
+                .style(theme.bad)
+                .print("Most error-prone keys:")
+                .reset();
             row += 1;
 
             for (key, rate) in error_prone_keys {
-                out.queue(cursor::MoveTo(4, row))?
-                    .queue(Print(format!("  '{}' - {:.1}% error rate", key, rate)))?;
+                frame
+                    .move_to(4, row)
+                    .print(format!("  '{}' - {:.1}% error rate", key, rate));
                 row += 1;
             }
         }
 
         row += 2;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?
-            .queue(Print("Press any key to continue..."))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.dim)
+            .print("Press any key to continue...")
+            .reset();
 
-        out.flush()?;
-        Ok(())
+        self.present(frame)
     }
 
-    pub fn render_menu(
+    pub fn render_menu<S: AsRef<str>>(
         &self,
         selected: usize,
-        options: &[&str],
+        options: &[S],
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        self.clear()?;
+        self.render_list(
+            "TypeHero - Select Practice Mode",
+            selected,
+            options,
+            "↑/↓ to navigate, Enter to select, ESC to quit",
+        )
+    }
+
+    pub fn render_list<S: AsRef<str>>(
+        &self,
+        title: &str,
+        selected: usize,
+        options: &[S],
+        hint: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let mut frame = self.frame();
+        let theme = self.theme();
 
         let mut row = 0;
 
         // Title
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(SetForegroundColor(Color::Cyan))?
-            .queue(Print("═".repeat(self.width as usize)))?;
+        frame
+            .move_to(0, row)
+            .style(theme.title)
+            .print("═".repeat(self.width() as usize));
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print("  TypeHero - Select Practice Mode"))?;
+        frame.move_to(0, row).print(format!("  {}", title));
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print("═".repeat(self.width as usize)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(0, row)
+            .print("═".repeat(self.width() as usize))
+            .reset();
         row += 2;
 
-        // Options
-        for (i, option) in options.iter().enumerate() {
-            out.queue(cursor::MoveTo(2, row))?;
+        // Options, scrolled to keep the selection in view when they don't
+        // all fit above the hint
+        let visible = (self.height() as usize).saturating_sub(row as usize + 3);
+        let first = if options.len() > visible {
+            frame
+                .move_to(2, row - 1)
+                .style(theme.dim)
+                .print(format!("{} of {}", selected + 1, options.len()))
+                .reset();
+            selected
+                .saturating_sub(visible / 2)
+                .min(options.len() - visible)
+        } else {
+            0
+        };
+        for (i, option) in options.iter().enumerate().skip(first).take(visible) {
+            let option = option.as_ref();
+            frame.move_to(2, row);
 
             if i == selected {
-                out.queue(SetBackgroundColor(Color::DarkBlue))?
-                    .queue(SetForegroundColor(Color::White))?
-                    .queue(Print(format!("  → {}  ", option)))?
-                    .queue(ResetColor)?;
+                frame
+                    .style(theme.selected)
+                    .print(format!("  → {}  ", option))
+                    .reset();
             } else {
-                out.queue(SetForegroundColor(Color::White))?
-                    .queue(Print(format!("    {}  ", option)))?
-                    .queue(ResetColor)?;
+                frame
+                    .style(theme.text)
+                    .print(format!("    {}  ", option))
+                    .reset();
             }
             row += 1;
         }
 
         row += 2;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?
-            .queue(Print("↑/↓ to navigate, Enter to select, ESC to quit"))?
-            .queue(ResetColor)?;
+        frame.move_to(2, row).style(theme.dim).print(hint).reset();
+
+        self.present(frame)
+    }
+
+    pub fn render_message(
+        &self,
+        title: &str,
+        lines: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let mut frame = self.frame();
+        let theme = self.theme();
+
+        let mut row = 0;
+
+        // Title
+        frame
+            .move_to(0, row)
+            .style(theme.title)
+            .print("═".repeat(self.width() as usize));
+        row += 1;
+        frame.move_to(0, row).print(format!("  {}", title));
+        row += 1;
+        frame
+            .move_to(0, row)
+            .print("═".repeat(self.width() as usize))
+            .reset();
+        row += 2;
+
+        for line in lines {
+            frame.move_to(2, row).print(line);
+            row += 1;
+        }
+
+        row += 1;
+        frame
+            .move_to(2, row)
+            .style(theme.dim)
+            .print("Press any key to continue...")
+            .reset();
 
-        out.flush()?;
-        Ok(())
+        self.present(frame)
     }
 
     pub fn render_guided_lesson(
@@ -308,150 +484,153 @@ impl Display {
         attempts: &[crate::guided_v2::AttemptStats],
         mastery_status: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        self.clear()?;
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let mut frame = self.frame();
+        let theme = self.theme();
 
         let mut row = 0;
 
         // Title
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(SetForegroundColor(Color::Cyan))?
-            .queue(Print("═".repeat(self.width as usize)))?;
+        frame
+            .move_to(0, row)
+            .style(theme.title)
+            .print("═".repeat(self.width() as usize));
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print(format!(
-                "  Guided Practice - Lesson {}/{}",
-                lesson_idx + 1,
-                total_lessons
-            )))?;
+        frame.move_to(0, row).print(format!(
+            "  Guided Practice - Lesson {}/{}",
+            lesson_idx + 1,
+            total_lessons
+        ));
         row += 1;
-        out.queue(cursor::MoveTo(0, row))?
-            .queue(Print("═".repeat(self.width as usize)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(0, row)
+            .print("═".repeat(self.width() as usize))
+            .reset();
         row += 2;
 
         // Lesson name
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::Yellow))?
-            .queue(Print(format!("Lesson: {}", lesson_name)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.accent)
+            .print(format!("Lesson: {}", lesson_name))
+            .reset();
         row += 1;
 
         // Active fingers (color-coded)
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?
-            .queue(Print("Active fingers: "))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.dim)
+            .print("Active fingers: ")
+            .reset();
 
         for (i, finger) in active_fingers.iter().enumerate() {
             if i > 0 {
-                out.queue(Print(", "))?;
+                frame.print(", ");
             }
-            out.queue(SetForegroundColor(finger.color()))?
-                .queue(Print(finger.name()))?
-                .queue(ResetColor)?;
+            frame
+                .style(theme.finger(finger))
+                .print(finger.name())
+                .reset();
         }
         row += 2;
 
         // Keyboard map showing active fingers
-        row = self.render_keyboard_map(&mut out, row, active_fingers)?;
+        row = self.render_keyboard_map(&mut frame, row, active_fingers);
         row += 1;
 
         // Mastery status
-        out.queue(cursor::MoveTo(2, row))?;
+        frame.move_to(2, row);
         if mastery_status.contains("MASTERED") {
-            out.queue(SetForegroundColor(Color::Green))?;
+            frame.style(theme.good);
         } else if mastery_status.contains("Progress") {
-            out.queue(SetForegroundColor(Color::Yellow))?;
+            frame.style(theme.accent);
         } else if mastery_status.contains("violation") {
-            out.queue(SetForegroundColor(Color::Red))?;
+            frame.style(theme.bad);
         } else {
-            out.queue(SetForegroundColor(Color::DarkGrey))?;
+            frame.style(theme.dim);
         }
-        out.queue(Print(format!("Status: {}", mastery_status)))?
-            .queue(ResetColor)?;
+        frame.print(format!("Status: {}", mastery_status)).reset();
         row += 2;
 
         // Current key instruction
         if let Some(ch) = current_char {
-            out.queue(cursor::MoveTo(2, row))?
-                .queue(SetForegroundColor(Color::Cyan))?
-                .queue(Print(format!("Next key: '{}'", ch)))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, row)
+                .style(theme.title)
+                .print(format!("Next key: '{}'", ch))
+                .reset();
         }
         row += 2;
 
         // Target text
-        out.queue(cursor::MoveTo(4, row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?;
+        frame.move_to(4, row).style(theme.dim);
 
-        for (i, ch) in text.chars().enumerate() {
+        for (i, ch) in crate::engine::graphemes(text).iter().enumerate() {
             if i == current_pos {
-                out.queue(SetBackgroundColor(Color::DarkBlue))?
-                    .queue(SetForegroundColor(Color::White))?
-                    .queue(Print(format!(" {} ", ch)))?
-                    .queue(ResetColor)?
-                    .queue(SetForegroundColor(Color::DarkGrey))?;
+                frame
+                    .style(theme.selected)
+                    .print(format!(" {} ", ch))
+                    .reset()
+                    .style(theme.dim);
             } else {
-                out.queue(Print(format!(" {} ", ch)))?;
+                frame.print(format!(" {} ", ch));
             }
         }
-        out.queue(ResetColor)?;
+        frame.reset();
         row += 3;
 
         // Typed text
-        out.queue(cursor::MoveTo(4, row))?;
+        frame.move_to(4, row);
         for (ch, correct) in typed {
             if *correct {
-                out.queue(SetForegroundColor(Color::Green))?
-                    .queue(Print(format!(" {} ", ch)))?;
+                frame.style(theme.correct).print(format!(" {} ", ch));
             } else {
-                out.queue(SetForegroundColor(Color::Red))?
-                    .queue(SetBackgroundColor(Color::DarkRed))?
-                    .queue(Print(format!(" {} ", ch)))?
-                    .queue(ResetColor)?;
+                frame
+                    .style(theme.incorrect)
+                    .print(format!(" {} ", ch))
+                    .reset();
             }
         }
-        out.queue(ResetColor)?;
+        frame.reset();
         row += 3;
 
         // Constraint notice
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::Yellow))?
-            .queue(Print("⚠ Only keys from active fingers are accepted"))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row)
+            .style(theme.accent)
+            .print("⚠ Only keys from active fingers are accepted")
+            .reset();
         row += 2;
 
         // Attempt history
         if !attempts.is_empty() {
-            out.queue(cursor::MoveTo(2, row))?
-                .queue(SetForegroundColor(Color::DarkGrey))?
-                .queue(Print("Recent attempts:"))?
-                .queue(ResetColor)?;
+            frame
+                .move_to(2, row)
+                .style(theme.dim)
+                .print("Recent attempts:")
+                .reset();
             row += 1;
 
             for (i, attempt) in attempts.iter().rev().take(5).enumerate() {
-                out.queue(cursor::MoveTo(4, row))?
-                    .queue(SetForegroundColor(if attempt.accuracy >= 95.0
-                        && attempt.illegal_keys == 0
-                    {
-                        Color::Green
-                    } else if attempt.illegal_keys > 0 {
-                        Color::Red
-                    } else if attempt.accuracy >= 85.0 {
-                        Color::Yellow
+                frame
+                    .move_to(4, row)
+                    .style(if attempt.illegal_keys > 0 {
+                        theme.bad
                     } else {
-                        Color::Red
-                    }))?
-                    .queue(Print(format!(
+                        theme.accuracy(attempt.accuracy)
+                    })
+                    .print(format!(
                         "#{}: {}ms - {:.0}% acc - {} errors - {} illegal",
                         attempts.len() - i,
                         attempt.duration_ms,
                         attempt.accuracy,
                         attempt.errors,
                         attempt.illegal_keys
-                    )))?
-                    .queue(ResetColor)?;
+                    ))
+                    .reset();
                 row += 1;
             }
             row += 1;
@@ -459,144 +638,195 @@ impl Display {
 
         // Instructions
         row += 1;
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(Color::DarkGrey))?;
+        frame.move_to(2, row).style(theme.dim);
 
         if typed.is_empty() && attempts.is_empty() {
             // First time in lesson
-            out.queue(Print(
-                "Press Enter to start, Ctrl-N/Ctrl-P to navigate, ESC to quit",
-            ))?;
+            frame.print("Press Enter to start, Ctrl-N/Ctrl-P to navigate, ESC to quit");
         } else if typed.is_empty() && !attempts.is_empty() {
             // Between attempts - auto-countdown
-            out.queue(Print(
-                "Auto-starting in 0.5s... (Ctrl-N/P to navigate, ESC to quit)",
-            ))?;
+            frame.print("Auto-starting in 0.5s... (Ctrl-N/P to navigate, ESC to quit)");
         } else {
             // During practice
-            out.queue(Print(
-                "Type using ONLY the active fingers - other keys will be rejected",
-            ))?;
+            frame.print("Type using ONLY the active fingers - other keys will be rejected");
         }
-        out.queue(ResetColor)?;
+        frame.reset();
 
-        out.flush()?;
-        Ok(())
+        self.present(frame)
     }
 
     pub fn render_countdown(&self, count: u8) -> Result<(), Box<dyn std::error::Error>> {
-        let mut out = stdout();
-        self.clear()?;
+        if self.too_small() {
+            return self.render_too_small();
+        }
 
-        let mid_col = self.width / 2;
+        let mut frame = self.frame();
+        let theme = self.theme();
+
+        let mid_col = self.width() / 2;
         let mid_row = 10;
 
-        out.queue(cursor::MoveTo(mid_col, mid_row))?
-            .queue(SetForegroundColor(Color::Yellow))?
-            .queue(Print(format!("{}", count)))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(mid_col, mid_row)
+            .style(theme.accent)
+            .print(format!("{}", count))
+            .reset();
 
-        out.flush()?;
-        Ok(())
+        self.present(frame)
     }
 
     pub fn render_keyboard_map(
         &self,
-        out: &mut std::io::Stdout,
+        frame: &mut Frame,
         row: u16,
         active_fingers: &[crate::guided_v2::Finger],
-    ) -> Result<u16, Box<dyn std::error::Error>> {
-        use crossterm::style::Color;
+    ) -> u16 {
         use crate::guided_v2::Finger;
 
-        let is_active = |finger: &Finger| active_fingers.contains(finger);
-
-        // Number row
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftPinky) { Finger::LeftPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("` "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftRing) { Finger::LeftRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("2 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftMiddle) { Finger::LeftMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("3 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftIndex) { Finger::LeftIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("4 5 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightIndex) { Finger::RightIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("6 7 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightMiddle) { Finger::RightMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("8 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightRing) { Finger::RightRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("9 "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightPinky) { Finger::RightPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("0 - = "))?
-            .queue(ResetColor)?;
-
-        // Top row
-        out.queue(cursor::MoveTo(2, row + 1))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftPinky) { Finger::LeftPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("q "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftRing) { Finger::LeftRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("w "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftMiddle) { Finger::LeftMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("e "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftIndex) { Finger::LeftIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("r t "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightIndex) { Finger::RightIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("y u "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightMiddle) { Finger::RightMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("i "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightRing) { Finger::RightRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("o "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightPinky) { Finger::RightPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("p [ ] "))?
-            .queue(ResetColor)?;
-
-        // Home row
-        out.queue(cursor::MoveTo(2, row + 2))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftPinky) { Finger::LeftPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("a "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftRing) { Finger::LeftRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("s "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftMiddle) { Finger::LeftMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("d "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftIndex) { Finger::LeftIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("f g "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightIndex) { Finger::RightIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("h j "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightMiddle) { Finger::RightMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("k "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightRing) { Finger::RightRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("l "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightPinky) { Finger::RightPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("; ' "))?
-            .queue(ResetColor)?;
-
-        // Bottom row
-        out.queue(cursor::MoveTo(2, row + 3))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftPinky) { Finger::LeftPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("z "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftRing) { Finger::LeftRing.color() } else { Color::DarkGrey }))?
-            .queue(Print("x "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftMiddle) { Finger::LeftMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print("c "))?
-            .queue(SetForegroundColor(if is_active(&Finger::LeftIndex) { Finger::LeftIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("v b "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightIndex) { Finger::RightIndex.color() } else { Color::DarkGrey }))?
-            .queue(Print("n m "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightMiddle) { Finger::RightMiddle.color() } else { Color::DarkGrey }))?
-            .queue(Print(", "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightRing) { Finger::RightRing.color() } else { Color::DarkGrey }))?
-            .queue(Print(". "))?
-            .queue(SetForegroundColor(if is_active(&Finger::RightPinky) { Finger::RightPinky.color() } else { Color::DarkGrey }))?
-            .queue(Print("/ "))?
-            .queue(ResetColor)?;
+        let theme = self.theme();
+        let style = |finger: &Finger| {
+            if active_fingers.contains(finger) {
+                theme.finger(finger)
+            } else {
+                theme.dim
+            }
+        };
+
+        // Number, top, home and bottom rows, split by the finger that
+        // covers each key
+        let rows: [&[(Finger, &str)]; 4] = [
+            &[
+                (Finger::LeftPinky, "` "),
+                (Finger::LeftRing, "2 "),
+                (Finger::LeftMiddle, "3 "),
+                (Finger::LeftIndex, "4 5 "),
+                (Finger::RightIndex, "6 7 "),
+                (Finger::RightMiddle, "8 "),
+                (Finger::RightRing, "9 "),
+                (Finger::RightPinky, "0 - = "),
+            ],
+            &[
+                (Finger::LeftPinky, "q "),
+                (Finger::LeftRing, "w "),
+                (Finger::LeftMiddle, "e "),
+                (Finger::LeftIndex, "r t "),
+                (Finger::RightIndex, "y u "),
+                (Finger::RightMiddle, "i "),
+                (Finger::RightRing, "o "),
+                (Finger::RightPinky, "p [ ] "),
+            ],
+            &[
+                (Finger::LeftPinky, "a "),
+                (Finger::LeftRing, "s "),
+                (Finger::LeftMiddle, "d "),
+                (Finger::LeftIndex, "f g "),
+                (Finger::RightIndex, "h j "),
+                (Finger::RightMiddle, "k "),
+                (Finger::RightRing, "l "),
+                (Finger::RightPinky, "; ' "),
+            ],
+            &[
+                (Finger::LeftPinky, "z "),
+                (Finger::LeftRing, "x "),
+                (Finger::LeftMiddle, "c "),
+                (Finger::LeftIndex, "v b "),
+                (Finger::RightIndex, "n m "),
+                (Finger::RightMiddle, ", "),
+                (Finger::RightRing, ". "),
+                (Finger::RightPinky, "/ "),
+            ],
+        ];
+
+        for (i, keys) in rows.iter().enumerate() {
+            frame.move_to(2, row + i as u16);
+            for (finger, label) in keys.iter() {
+                frame.style(style(finger)).print(label);
+            }
+            frame.reset();
+        }
 
         // Space bar
-        out.queue(cursor::MoveTo(2, row + 4))?
-            .queue(SetForegroundColor(if is_active(&Finger::Thumbs) { Finger::Thumbs.color() } else { Color::DarkGrey }))?
-            .queue(Print("        [space]        "))?
-            .queue(ResetColor)?;
+        frame
+            .move_to(2, row + 4)
+            .style(style(&Finger::Thumbs))
+            .print("        [space]        ")
+            .reset();
+
+        row + 6
+    }
+}
+
+/// Columns a tab is drawn as. Frames expand tabs to spaces rather than leave
+/// them to the terminal, whose tab stops don't know where our panes start.
+pub const TAB_WIDTH: usize = 4;
+
+/// Columns one grapheme cluster occupies on screen: 2 for wide CJK and most
+/// emoji, 0 for lone combining marks.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme == "\t" {
+        TAB_WIDTH
+    } else {
+        grapheme.width()
+    }
+}
+
+/// Display width of a string, measured per grapheme.
+pub fn text_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Split graphemes into lines no wider than `width` columns, breaking after
+/// spaces. Each line keeps its trailing space so every grapheme, spaces
+/// included, lands on exactly one line. Words longer than a whole line are
+/// broken wherever they overflow.
+fn wrap_graphemes(graphemes: &[&str], width: usize) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    let mut line_width = 0;
+    let mut i = 0;
+
+    while i < graphemes.len() {
+        // The next word and the spaces after it
+        let word_start = i;
+        while i < graphemes.len() && graphemes[i] != " " {
+            i += 1;
+        }
+        let word_end = i;
+        while i < graphemes.len() && graphemes[i] == " " {
+            i += 1;
+        }
+
+        let word_width: usize = graphemes[word_start..word_end]
+            .iter()
+            .map(|g| grapheme_width(g))
+            .sum();
+        let space_width = i - word_end;
+
+        if line_width > 0 && line_width + word_width > width {
+            lines.push(line_start..word_start);
+            line_start = word_start;
+            line_width = 0;
+        }
+
+        if word_width > width {
+            // Too long for any line - hard-break it
+            for (j, g) in graphemes[word_start..word_end].iter().enumerate() {
+                let g_width = grapheme_width(g);
+                if line_width > 0 && line_width + g_width > width {
+                    lines.push(line_start..word_start + j);
+                    line_start = word_start + j;
+                    line_width = 0;
+                }
+                line_width += g_width;
+            }
+        } else {
+            line_width += word_width;
+        }
+        line_width += space_width;
+    }
 
-        Ok(row + 6)
+    if line_start < graphemes.len() || lines.is_empty() {
+        lines.push(line_start..graphemes.len());
     }
+    lines
 }
@@ -1,36 +1,157 @@
 use crate::display::Display;
-use crate::input::{read_key, InputEvent};
+use crate::exercises::{Modifiers, Progress, TextSource};
+use crate::input::{read_key, Composed, Composer, InputEvent};
+use crate::srs::PatternSample;
 use crate::stats::{SessionSummary, Stats};
 use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Ask the text source for more once fewer than this many graphemes remain
+const LOOKAHEAD_CHARS: usize = 30;
+// Keystrokes considered when reporting recent accuracy/latency to a source
+const RECENT_WINDOW: usize = 50;
 
 pub struct TypingSession {
     text: String,
-    typed: Vec<(char, bool)>, // (char, was_correct)
+    source: Option<Box<dyn TextSource>>,
+    // (was_correct, latency_ms) for the last RECENT_WINDOW keystrokes
+    recent: VecDeque<(bool, Option<f64>)>,
+    typed: Vec<(String, bool)>, // (grapheme, was_correct)
+    composer: Composer,
     start_time: Instant,
     last_char_time: Instant,
     current_pos: usize,
     errors: usize,
     stats: Stats,
     quit_warning_shown: bool,
+    // Quitting below this completion (%) needs a second ESC
+    quit_warning_below: f64,
+    key_samples: HashMap<String, PatternSample>,
+    bigram_samples: HashMap<String, PatternSample>,
+    modifiers: Modifiers,
+    mode: &'static str,
+}
+
+/// Split text into the units the user types: NFC-normalised grapheme
+/// clusters, so "é", "ñ" or a multi-codepoint emoji each count as one.
+pub fn graphemes(text: &str) -> Vec<String> {
+    let text: String = text.nfc().collect();
+    text.graphemes(true).map(String::from).collect()
 }
 
 impl TypingSession {
     pub fn new(text: String, stats: Stats) -> Self {
         Self {
-            text,
+            text: text.nfc().collect(),
+            source: None,
+            recent: VecDeque::new(),
             typed: Vec::new(),
+            composer: Composer::default(),
             start_time: Instant::now(),
             last_char_time: Instant::now(),
             current_pos: 0,
             errors: 0,
             stats,
             quit_warning_shown: false,
+            quit_warning_below: 50.0,
+            key_samples: HashMap::new(),
+            bigram_samples: HashMap::new(),
+            modifiers: Modifiers::default(),
+            mode: "",
+        }
+    }
+
+    /// Record which text modifiers shaped this session's text.
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Tag the stored session with the practice mode it came from.
+    pub fn with_mode(mut self, mode: &'static str) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Ask for a second ESC when quitting before `percent` of the text is
+    /// typed.
+    pub fn with_quit_warning(mut self, percent: u32) -> Self {
+        self.quit_warning_below = percent as f64;
+        self
+    }
+
+    /// Start a session whose text is requested chunk by chunk from `source`.
+    pub fn with_source(mut source: Box<dyn TextSource>, stats: Stats) -> Self {
+        let progress = Progress {
+            recent_accuracy: 100.0,
+            recent_latency_ms: None,
+            chars_typed: 0,
+        };
+        let text = source.next_chunk(&progress).unwrap_or_default();
+        let mut session = Self::new(text, stats);
+        session.source = Some(source);
+        session
+    }
+
+    fn graphemes(&self) -> Vec<String> {
+        graphemes(&self.text)
+    }
+
+    fn progress(&self) -> Progress {
+        let recent_accuracy = if self.recent.is_empty() {
+            100.0
+        } else {
+            let correct = self.recent.iter().filter(|(c, _)| *c).count();
+            (correct as f64 / self.recent.len() as f64) * 100.0
+        };
+        let latencies: Vec<f64> = self.recent.iter().filter_map(|(_, ms)| *ms).collect();
+        let recent_latency_ms = if latencies.is_empty() {
+            None
+        } else {
+            Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+        };
+
+        Progress {
+            recent_accuracy,
+            recent_latency_ms,
+            chars_typed: self.typed.len(),
         }
     }
 
-    fn chars(&self) -> Vec<char> {
-        self.text.chars().collect()
+    /// Top up the text from the source when the user gets close to the end.
+    /// Returns true if more text was appended.
+    fn request_more_text(&mut self) -> bool {
+        let remaining = self
+            .text
+            .graphemes(true)
+            .count()
+            .saturating_sub(self.current_pos);
+        if remaining >= LOOKAHEAD_CHARS {
+            return false;
+        }
+
+        let progress = self.progress();
+        let Some(source) = self.source.as_mut() else {
+            return false;
+        };
+
+        match source.next_chunk(&progress) {
+            Some(chunk) if !chunk.is_empty() => {
+                if !self.text.is_empty() {
+                    self.text.push(' ');
+                }
+                self.text.extend(chunk.nfc());
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.source = None;
+                false
+            }
+        }
     }
 
     fn elapsed_secs(&self) -> f64 {
@@ -59,8 +180,48 @@ impl TypingSession {
         self.raw_wpm() * acc * acc
     }
 
+    /// Score one typed grapheme against the expected one and advance.
+    fn record(&mut self, typed: String, graphemes: &[String]) {
+        let expected = &graphemes[self.current_pos];
+        let correct = typed == *expected;
+
+        // Record the keystroke
+        self.typed.push((typed, correct));
+        if !correct {
+            self.errors += 1;
+        }
+
+        // Track in stats
+        let mut latency_ms = None;
+        self.stats.record_keystroke(expected, correct);
+        self.key_samples
+            .entry(expected.clone())
+            .or_default()
+            .record(correct, None);
+
+        // Track bigram timing
+        if self.current_pos > 0 {
+            let prev = &graphemes[self.current_pos - 1];
+            let duration = self.last_char_time.elapsed();
+            latency_ms = Some(duration.as_secs_f64() * 1000.0);
+            self.stats.record_bigram((prev, expected), duration);
+            self.bigram_samples
+                .entry(format!("{}{}", prev, expected))
+                .or_default()
+                .record(correct, latency_ms);
+        }
+
+        self.recent.push_back((correct, latency_ms));
+        if self.recent.len() > RECENT_WINDOW {
+            self.recent.pop_front();
+        }
+
+        self.last_char_time = Instant::now();
+        self.current_pos += 1;
+    }
+
     pub fn run(&mut self, display: &Display) -> Result<SessionSummary, Box<dyn std::error::Error>> {
-        let chars = self.chars();
+        let mut graphemes = self.graphemes();
 
         loop {
             // Render current state
@@ -77,49 +238,50 @@ impl TypingSession {
 
             // Check for input
             match read_key(Duration::from_millis(50))? {
+                // The text isn't on screen, so don't score blind typing
+                InputEvent::Char(_) if display.too_small() => {}
                 InputEvent::Char(ch) => {
-                    if self.current_pos >= chars.len() {
-                        continue; // Ignore extra input after completion
-                    }
-
-                    let expected = chars[self.current_pos];
-                    let correct = ch == expected;
-
-                    // Record the keystroke
-                    self.typed.push((ch, correct));
-                    if !correct {
-                        self.errors += 1;
-                    }
+                    // A dead key or combining sequence may finish a grapheme
+                    // and start the next in one keystroke
+                    let mut next = Some(ch);
+                    while let Some(ch) = next.take() {
+                        if self.current_pos >= graphemes.len() {
+                            break; // Ignore extra input after completion
+                        }
 
-                    // Track in stats
-                    self.stats.record_keystroke(expected, correct);
+                        let typed = match self.composer.push(ch, &graphemes[self.current_pos]) {
+                            Composed::Pending => break,
+                            Composed::Grapheme(typed) => typed,
+                            Composed::Interrupted(typed, ch) => {
+                                next = Some(ch);
+                                typed
+                            }
+                        };
+                        self.record(typed, &graphemes);
 
-                    // Track bigram timing
-                    if self.current_pos > 0 {
-                        let prev = chars[self.current_pos - 1];
-                        let duration = self.last_char_time.elapsed();
-                        self.stats.record_bigram((prev, expected), duration);
+                        // Let an incremental source extend the text
+                        while self.request_more_text() {
+                            graphemes = self.graphemes();
+                        }
                     }
 
-                    self.last_char_time = Instant::now();
-                    self.current_pos += 1;
-
                     // Check if test is complete
-                    if self.current_pos >= chars.len() {
+                    if self.current_pos >= graphemes.len() {
                         break;
                     }
                 }
                 InputEvent::Escape => {
                     // Check if quitting too early
-                    let completion_pct = (self.current_pos as f64 / chars.len() as f64) * 100.0;
+                    let completion_pct = (self.current_pos as f64 / graphemes.len() as f64) * 100.0;
 
-                    if completion_pct < 50.0 && !self.quit_warning_shown {
+                    if completion_pct < self.quit_warning_below && !self.quit_warning_shown {
                         self.quit_warning_shown = true;
                         // Warning will be shown on next render
                     } else {
                         break;
                     }
                 }
+                InputEvent::Resize(width, height) => display.resize(width, height),
                 _ => {}
             }
         }
@@ -133,6 +295,8 @@ impl TypingSession {
             raw_wpm: self.raw_wpm(),
             accuracy: self.accuracy(),
             adjusted_wpm: self.adjusted_wpm(),
+            modifiers: self.modifiers.label(),
+            mode: self.mode.to_string(),
         };
 
         // Add to stats and save
@@ -142,36 +306,52 @@ impl TypingSession {
         Ok(summary)
     }
 
+    pub fn is_complete(&self) -> bool {
+        self.current_pos >= self.text.graphemes(true).count()
+    }
+
     pub fn stats(&self) -> &Stats {
         &self.stats
     }
+
+    pub fn key_samples(&self) -> &HashMap<String, PatternSample> {
+        &self.key_samples
+    }
+
+    pub fn bigram_samples(&self) -> &HashMap<String, PatternSample> {
+        &self.bigram_samples
+    }
 }
 
 pub fn show_summary(
     display: &Display,
     summary: &SessionSummary,
     stats: &Stats,
+) -> Result<(), Box<dyn std::error::Error>> {
+    show_summary_with_notes(display, summary, stats, &[])
+}
+
+/// Like `show_summary`, with extra lines such as a quote's attribution.
+pub fn show_summary_with_notes(
+    display: &Display,
+    summary: &SessionSummary,
+    stats: &Stats,
+    notes: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
     let slowest = stats.slowest_bigrams(5);
     let error_prone = stats.most_error_prone_keys(5);
 
-    display.render_summary(
-        summary.duration_secs,
-        summary.total_chars,
-        summary.errors,
-        summary.raw_wpm,
-        summary.accuracy,
-        summary.adjusted_wpm,
-        &slowest,
-        &error_prone,
-    )?;
+    display.render_summary(summary, &slowest, &error_prone, notes)?;
 
     // Wait for any key
     loop {
-        if let InputEvent::Char(_) | InputEvent::Enter | InputEvent::Escape =
-            read_key(Duration::from_millis(100))?
-        {
-            break;
+        match read_key(Duration::from_millis(100))? {
+            InputEvent::Char(_) | InputEvent::Enter | InputEvent::Escape => break,
+            InputEvent::Resize(width, height) => {
+                display.resize(width, height);
+                display.render_summary(summary, &slowest, &error_prone, notes)?;
+            }
+            _ => {}
         }
     }
 
@@ -1,92 +1,499 @@
+use crate::chunker;
+use crate::language::Language;
+use crate::markov::MarkovChain;
+use crate::wordlist::WordList;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-
-const COMMON_WORDS: &[&str] = &[
-    "the", "be", "to", "of", "and", "a", "in", "that", "have", "it", "for", "not", "on", "with",
-    "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we", "say", "her",
-    "she", "or", "an", "will", "my", "one", "all", "would", "there", "their", "what", "so", "up",
-    "out", "if", "about", "who", "get", "which", "go", "me", "when", "make", "can", "like", "time",
-    "no", "just", "him", "know", "take", "people", "into", "year", "your", "good", "some", "could",
-    "them", "see", "other", "than", "then", "now", "look", "only", "come", "its", "over", "think",
-    "also", "back", "after", "use", "two", "how", "our", "work", "first", "well", "way", "even",
-    "new", "want", "because", "any", "these", "give", "day", "most", "us",
-];
+use std::path::Path;
 
-const CODE_PATTERNS: &[&str] = &[
-    "const", "let", "var", "function", "return", "if", "else", "for", "while", "break", "continue",
-    "class", "import", "export", "async", "await", "try", "catch", "throw", "new", "this", "super",
-    "extends", "implements", "interface", "type", "enum", "public", "private", "protected", "static",
-    "void", "null", "undefined", "true", "false", "=>", "===", "!==", "&&", "||", "++", "--",
-    "{}", "[]", "()", "<>", "/*", "*/", "//", "/**", "*/", "${}", "`", "->", "::", "fn", "mut",
-    "struct", "impl", "trait", "pub", "use", "mod", "crate", "self", "super", "enum", "match",
-    "Some", "None", "Ok", "Err", "Vec", "String", "Result", "Option",
-];
+// (file name, code) for every file in code-samples/solutions, listed by
+// build.rs
+const BUNDLED_SOLUTIONS: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/code_samples.rs"));
+const BUNDLED_PROBLEMS: &str = include_str!("../code-samples/problem-statements.json");
+const BUNDLED_SNIPPETS: &str = include_str!("../practice_problems.json");
+const BUNDLED_QUOTES: &str = include_str!("../quotes/quotes.json");
 
 pub enum ExerciseMode {
-    RandomWords,
     Code,
-    Targeted(Vec<String>),
-    #[allow(dead_code)]
+    Words(WordList),
+    Targeted(Vec<String>, &'static Language),
     Custom(String),
+    /// Markov-generated prose, weighted toward `weak_bigrams` if any
+    Prose {
+        chain: MarkovChain,
+        weak_bigrams: Vec<String>,
+    },
+}
+
+/// Optional extras layered over word-based exercises so prose practice
+/// includes Shift, punctuation and digits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub capitalize: bool,
+    pub punctuation: bool,
+    pub numbers: bool,
+}
+
+impl Modifiers {
+    /// Short label stored with each session, e.g. "caps+punct". Empty when
+    /// no modifiers are on.
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.capitalize {
+            parts.push("caps");
+        }
+        if self.punctuation {
+            parts.push("punct");
+        }
+        if self.numbers {
+            parts.push("nums");
+        }
+        parts.join("+")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.capitalize || self.punctuation || self.numbers)
+    }
+
+    /// Rewrite space-separated words into sentence-like text.
+    pub fn apply(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+
+        let mut rng = rand::thread_rng();
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut out: Vec<String> = Vec::with_capacity(words.len());
+        let mut sentence_left: usize = rng.gen_range(5..15);
+        let mut sentence_start = true;
+        let mut i = 0;
+
+        while i < words.len() {
+            let mut word = if self.numbers && rng.gen_bool(0.08) {
+                random_number(&mut rng)
+            } else {
+                words[i].to_string()
+            };
+
+            if self.capitalize && sentence_start {
+                word = capitalize(&word);
+            }
+            sentence_start = false;
+            sentence_left -= 1;
+            let last = i + 1 == words.len();
+
+            if self.punctuation && !last && sentence_left > 1 {
+                let roll: f64 = rng.gen();
+                if roll < 0.03 {
+                    word = format!("\"{}\"", word);
+                } else if roll < 0.06 {
+                    // Wrap this and up to two following words in parentheses
+                    let span = rng.gen_range(1..=3).min(words.len() - i);
+                    let mut inner = vec![word];
+                    inner.extend(words[i + 1..i + span].iter().map(|w| w.to_string()));
+                    i += span - 1;
+                    sentence_left = sentence_left.saturating_sub(span - 1).max(1);
+                    word = format!("({})", inner.join(" "));
+                } else if roll < 0.16 {
+                    word.push(',');
+                } else if roll < 0.18 {
+                    word.push(if rng.gen_bool(0.5) { ';' } else { ':' });
+                }
+            }
+
+            let last = i + 1 == words.len();
+            if sentence_left == 0 || last {
+                if self.punctuation {
+                    let roll: f64 = rng.gen();
+                    word.push(if roll < 0.1 {
+                        '?'
+                    } else if roll < 0.15 {
+                        '!'
+                    } else {
+                        '.'
+                    });
+                }
+                sentence_left = rng.gen_range(5..15);
+                sentence_start = true;
+            }
+
+            out.push(word);
+            i += 1;
+        }
+
+        out.join(" ")
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn random_number<R: Rng>(rng: &mut R) -> String {
+    match rng.gen_range(0..4) {
+        // Year
+        0 => rng.gen_range(1900..=2030).to_string(),
+        // Decimal
+        1 => format!("{:.2}", rng.gen_range(0.0..1000.0)),
+        // ID
+        2 => rng.gen_range(1000..100_000).to_string(),
+        // Small count
+        _ => rng.gen_range(2..100).to_string(),
+    }
+}
+
+/// Running performance the engine reports when it asks a source for more text.
+pub struct Progress {
+    pub recent_accuracy: f64,
+    pub recent_latency_ms: Option<f64>,
+    pub chars_typed: usize,
+}
+
+/// Supplies exercise text incrementally so a session can react to how the
+/// user is doing. Returning `None` ends the session once the text is typed.
+pub trait TextSource {
+    fn next_chunk(&mut self, progress: &Progress) -> Option<String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// Adjusts each chunk to the user's recent accuracy and speed: weak-bigram
+/// words while they are cruising, short common words once accuracy drops
+/// below the 95% target.
+pub struct AdaptiveSource {
+    weak_bigrams: Vec<String>,
+    language: &'static Language,
+    modifiers: Modifiers,
+    baseline_ms: f64,
+    words_per_chunk: usize,
+    chunks_remaining: usize,
+}
+
+impl AdaptiveSource {
+    pub fn new(
+        weak_bigrams: Vec<String>,
+        language: &'static Language,
+        baseline_ms: f64,
+        total_words: usize,
+        modifiers: Modifiers,
+    ) -> Self {
+        // Build the dictionary now rather than mid-test, on the first hard chunk
+        if !weak_bigrams.is_empty() {
+            language.dictionary();
+        }
+        let words_per_chunk = 10;
+        Self {
+            weak_bigrams,
+            language,
+            modifiers,
+            baseline_ms,
+            words_per_chunk,
+            chunks_remaining: total_words.div_ceil(words_per_chunk),
+        }
+    }
+
+    fn pick_level(&self, progress: &Progress) -> Level {
+        if progress.chars_typed == 0 {
+            return Level::Normal;
+        }
+        if progress.recent_accuracy < 95.0 {
+            return Level::Easy;
+        }
+
+        let fast_enough = match progress.recent_latency_ms {
+            Some(ms) if self.baseline_ms > 0.0 => ms <= self.baseline_ms * 1.1,
+            _ => true,
+        };
+        if progress.recent_accuracy >= 97.0 && fast_enough {
+            Level::Hard
+        } else {
+            Level::Normal
+        }
+    }
+}
+
+impl TextSource for AdaptiveSource {
+    fn next_chunk(&mut self, progress: &Progress) -> Option<String> {
+        if self.chunks_remaining == 0 {
+            return None;
+        }
+        self.chunks_remaining -= 1;
+
+        let text = match self.pick_level(progress) {
+            Level::Easy => {
+                let mut rng = rand::thread_rng();
+                let mut easy = self.language.wordlist();
+                easy.words.retain(|w| w.chars().count() <= 4);
+                easy.random_words(self.words_per_chunk, &mut rng).join(" ")
+            }
+            Level::Normal => generate_exercise(
+                &ExerciseMode::Words(self.language.wordlist()),
+                self.words_per_chunk,
+            ),
+            Level::Hard => generate_exercise(
+                &ExerciseMode::Targeted(self.weak_bigrams.clone(), self.language),
+                self.words_per_chunk,
+            ),
+        };
+        Some(self.modifiers.apply(&text))
+    }
 }
 
 pub struct CodeSample {
     pub code: String,
     pub language: String,
+    /// What the code solves, when the file is a `problem-N` solution with a
+    /// matching entry in `problem-statements.json`
+    pub problem: Option<Problem>,
+    /// Where in its file the code comes from: "part 2 of 3" of a long
+    /// solution, or file and line for code read with `typehero code --from`
+    pub source: Option<String>,
+}
+
+impl CodeSample {
+    /// The problem's difficulty tier, or "" without a problem statement.
+    pub fn difficulty(&self) -> &str {
+        self.problem
+            .as_ref()
+            .map(|p| p.difficulty.as_str())
+            .unwrap_or("")
+    }
+}
+
+/// An entry in `code-samples/problem-statements.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Problem {
+    pub id: u32,
+    pub statement: String,
+    pub difficulty: String,
+}
+
+#[derive(Deserialize)]
+struct ProblemFile {
+    problems: Vec<Problem>,
+}
+
+/// Languages and difficulty tiers of the bundled code, as written in the
+/// config and on the command line.
+pub const CODE_LANGUAGES: &[&str] = &["javascript", "typescript", "python", "rust", "go"];
+pub const CODE_DIFFICULTIES: &[&str] = &["easy", "medium", "hard"];
+
+/// A language `typehero code --from` practices on.
+pub struct SourceLanguage {
+    /// As written in the config and on the command line
+    pub key: &'static str,
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+}
+
+/// Every language with source files worth practicing on; the bundled code
+/// covers the first few (`CODE_LANGUAGES`).
+pub const SOURCE_LANGUAGES: &[SourceLanguage] = &[
+    SourceLanguage {
+        key: "javascript",
+        name: "JavaScript",
+        extensions: &["js", "jsx"],
+    },
+    SourceLanguage {
+        key: "typescript",
+        name: "TypeScript",
+        extensions: &["ts", "tsx"],
+    },
+    SourceLanguage {
+        key: "python",
+        name: "Python",
+        extensions: &["py"],
+    },
+    SourceLanguage {
+        key: "rust",
+        name: "Rust",
+        extensions: &["rs"],
+    },
+    SourceLanguage {
+        key: "go",
+        name: "Go",
+        extensions: &["go"],
+    },
+    SourceLanguage {
+        key: "c",
+        name: "C",
+        extensions: &["c", "h"],
+    },
+    SourceLanguage {
+        key: "cpp",
+        name: "C++",
+        extensions: &["cc", "cpp", "hpp"],
+    },
+    SourceLanguage {
+        key: "java",
+        name: "Java",
+        extensions: &["java"],
+    },
+    SourceLanguage {
+        key: "csharp",
+        name: "C#",
+        extensions: &["cs"],
+    },
+    SourceLanguage {
+        key: "ruby",
+        name: "Ruby",
+        extensions: &["rb"],
+    },
+    SourceLanguage {
+        key: "php",
+        name: "PHP",
+        extensions: &["php"],
+    },
+    SourceLanguage {
+        key: "scala",
+        name: "Scala",
+        extensions: &["scala"],
+    },
+    SourceLanguage {
+        key: "shell",
+        name: "Shell",
+        extensions: &["sh"],
+    },
+    SourceLanguage {
+        key: "lua",
+        name: "Lua",
+        extensions: &["lua"],
+    },
+    SourceLanguage {
+        key: "haskell",
+        name: "Haskell",
+        extensions: &["hs"],
+    },
+];
+
+/// Display name for a code language, given as a file extension (CodeJam
+/// solutions) or as its key (one-liner snippets).
+pub fn code_language_name(language: &str) -> &str {
+    source_language(language).map_or(language, |l| l.name)
+}
+
+/// The `SOURCE_LANGUAGES` key for a language key or extension, if any.
+pub fn code_language_key(language: &str) -> Option<&'static str> {
+    source_language(language).map(|l| l.key)
+}
+
+fn source_language(language: &str) -> Option<&'static SourceLanguage> {
+    SOURCE_LANGUAGES
+        .iter()
+        .find(|l| l.key == language || l.extensions.contains(&language))
+}
+
+/// Which languages and difficulty tiers to practice in CodeJam and Code
+/// One-Liners. An empty list allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CodeFilter {
+    pub languages: Vec<String>,
+    pub difficulties: Vec<String>,
+}
+
+impl CodeFilter {
+    pub fn matches(&self, language: &str, difficulty: &str) -> bool {
+        let language = code_language_key(language).unwrap_or(language);
+        (self.languages.is_empty() || self.languages.iter().any(|l| l == language))
+            && (self.difficulties.is_empty() || self.difficulties.iter().any(|d| d == difficulty))
+    }
+
+    /// Check the entries against the bundled languages and tiers.
+    pub fn validate(&self) -> Result<(), String> {
+        for language in &self.languages {
+            if !CODE_LANGUAGES.contains(&language.as_str()) {
+                return Err(format!(
+                    "unknown code language '{}'. Available: {}",
+                    language,
+                    CODE_LANGUAGES.join(", ")
+                ));
+            }
+        }
+        for difficulty in &self.difficulties {
+            if !CODE_DIFFICULTIES.contains(&difficulty.as_str()) {
+                return Err(format!(
+                    "unknown difficulty '{}'. Available: {}",
+                    difficulty,
+                    CODE_DIFFICULTIES.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// "Rust, Go - hard", or "all" when nothing is filtered out.
+    pub fn label(&self) -> String {
+        let languages: Vec<&str> = self
+            .languages
+            .iter()
+            .map(|l| code_language_name(l))
+            .collect();
+        match (languages.is_empty(), self.difficulties.is_empty()) {
+            (true, true) => "all".to_string(),
+            (false, true) => languages.join(", "),
+            (true, false) => self.difficulties.join(", "),
+            (false, false) => format!(
+                "{} - {}",
+                languages.join(", "),
+                self.difficulties.join(", ")
+            ),
+        }
+    }
 }
 
 pub fn generate_exercise(mode: &ExerciseMode, word_count: usize) -> String {
     let mut rng = rand::thread_rng();
 
     match mode {
-        ExerciseMode::RandomWords => {
-            let words: Vec<&str> = (0..word_count)
-                .map(|_| *COMMON_WORDS.choose(&mut rng).unwrap())
-                .collect();
-            words.join(" ")
-        }
-        ExerciseMode::Code => {
-            let patterns: Vec<&str> = (0..word_count)
-                .map(|_| *CODE_PATTERNS.choose(&mut rng).unwrap())
-                .collect();
-            patterns.join(" ")
-        }
-        ExerciseMode::Targeted(bigrams) => {
+        ExerciseMode::Code => WordList::code()
+            .random_words(word_count, &mut rng)
+            .join(" "),
+        ExerciseMode::Words(list) => list.random_words(word_count, &mut rng).join(" "),
+        ExerciseMode::Targeted(bigrams, language) => {
             if bigrams.is_empty() {
-                return generate_exercise(&ExerciseMode::RandomWords, word_count);
+                return generate_exercise(&ExerciseMode::Words(language.wordlist()), word_count);
             }
 
-            // Generate text heavily featuring problem bigrams
-            let mut text = String::new();
+            // Generate text heavily featuring problem bigrams, using real words
+            let dictionary = language.dictionary();
+            let mut words = Vec::with_capacity(word_count);
             for _ in 0..word_count {
                 let bigram = bigrams.choose(&mut rng).unwrap();
 
-                // Find or create words containing this bigram
-                let words_with_bigram: Vec<&str> = COMMON_WORDS
-                    .iter()
-                    .filter(|w| w.contains(bigram.as_str()))
-                    .copied()
-                    .collect();
-
-                if !words_with_bigram.is_empty() {
-                    text.push_str(words_with_bigram.choose(&mut rng).unwrap());
-                } else {
-                    // Generate nonsense word with the bigram
-                    let prefix: String = (0..rng.gen_range(1..3))
-                        .map(|_| rng.gen_range(b'a'..=b'z') as char)
-                        .collect();
-                    let suffix: String = (0..rng.gen_range(1..3))
-                        .map(|_| rng.gen_range(b'a'..=b'z') as char)
-                        .collect();
-                    text.push_str(&format!("{}{}{}", prefix, bigram, suffix));
+                match dictionary.targeted_word(bigram, &mut rng) {
+                    Some(word) => words.push(word),
+                    // No word can contain it (digits, symbols) - drill it on its own
+                    None => {
+                        let token = bigram.trim();
+                        if !token.is_empty() {
+                            words.push(token.to_string());
+                        }
+                    }
                 }
-                text.push(' ');
             }
-            text.trim().to_string()
+
+            if words.is_empty() {
+                return generate_exercise(&ExerciseMode::Words(language.wordlist()), word_count);
+            }
+            words.join(" ")
         }
         ExerciseMode::Custom(text) => text.clone(),
+        ExerciseMode::Prose {
+            chain,
+            weak_bigrams,
+        } => chain.generate(word_count, weak_bigrams, &mut rng),
     }
 }
 
@@ -118,45 +525,190 @@ pub fn generate_key_drill(ch: char, reps: usize) -> String {
     text
 }
 
-pub fn load_random_code_sample() -> Option<CodeSample> {
-    let mut rng = rand::thread_rng();
+/// Every bundled solution, plus any in `~/.typehero/code-samples/solutions`
+/// (which replace bundled files of the same name), with their problem
+/// statements, ordered by problem number.
+pub fn load_code_samples() -> Vec<CodeSample> {
+    let mut files: Vec<(String, String)> = BUNDLED_SOLUTIONS
+        .iter()
+        .map(|(name, code)| (name.to_string(), code.to_string()))
+        .collect();
+    let mut problems = parse_problems(BUNDLED_PROBLEMS);
 
-    // Try multiple possible locations for code-samples
-    let possible_paths = vec![
-        PathBuf::from("code-samples/solutions"),
-        PathBuf::from("../code-samples/solutions"),
-        dirs::home_dir()?.join(".typehero/code-samples/solutions"),
-    ];
-
-    for solutions_dir in possible_paths {
-        if let Ok(entries) = fs::read_dir(&solutions_dir) {
-            let files: Vec<PathBuf> = entries
-                .filter_map(|e| e.ok())
-                .map(|e| e.path())
-                .filter(|p| {
-                    p.extension()
-                        .and_then(|ext| ext.to_str())
-                        .map(|ext| matches!(ext, "js" | "ts" | "py" | "rs" | "go"))
-                        .unwrap_or(false)
-                })
-                .collect();
-
-            if let Some(file) = files.choose(&mut rng) {
-                let language = file
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("txt")
-                    .to_string();
-
-                if let Ok(content) = fs::read_to_string(file) {
-                    return Some(CodeSample {
-                        code: content,
-                        language,
-                    });
+    if let Some(dir) = dirs::home_dir().map(|home| home.join(".typehero/code-samples")) {
+        if let Ok(entries) = fs::read_dir(dir.join("solutions")) {
+            for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                if let Ok(code) = fs::read_to_string(&path) {
+                    files.retain(|(existing, _)| existing != name);
+                    files.push((name.to_string(), code));
                 }
             }
         }
+        if let Ok(content) = fs::read_to_string(dir.join("problem-statements.json")) {
+            layer(&mut problems, parse_problems(&content), |p| p.id);
+        }
+    }
+
+    files.retain(|(name, _)| {
+        Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| matches!(ext, "js" | "ts" | "py" | "rs" | "go"))
+            .unwrap_or(false)
+    });
+    files.sort_by_key(|(name, _)| (problem_id(name), name.clone()));
+
+    files
+        .into_iter()
+        .flat_map(|(name, code)| {
+            let language = Path::new(&name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("txt")
+                .to_string();
+            let problem = problem_id(&name)
+                .and_then(|id| problems.iter().find(|p| p.id == id))
+                .cloned();
+            // Long solutions are practiced a part at a time
+            let chunks = chunker::chunk(&code, &language);
+            let parts = chunks.len();
+            chunks
+                .into_iter()
+                .enumerate()
+                .map(move |(i, chunk)| CodeSample {
+                    code: chunk.code,
+                    language: language.clone(),
+                    problem: problem.clone(),
+                    source: (parts > 1).then(|| format!("part {} of {}", i + 1, parts)),
+                })
+        })
+        .collect()
+}
+
+fn parse_problems(content: &str) -> Vec<Problem> {
+    serde_json::from_str::<ProblemFile>(content)
+        .map(|file| file.problems)
+        .unwrap_or_default()
+}
+
+/// Add user entries to bundled ones, replacing any with the same id.
+fn layer<T>(bundled: &mut Vec<T>, user: Vec<T>, id: impl Fn(&T) -> u32) {
+    for item in user {
+        bundled.retain(|existing| id(existing) != id(&item));
+        bundled.push(item);
     }
+}
+
+/// A random sample among those the filter allows.
+pub fn random_code_sample(mut samples: Vec<CodeSample>, filter: &CodeFilter) -> Option<CodeSample> {
+    samples.retain(|s| filter.matches(&s.language, s.difficulty()));
+    if samples.is_empty() {
+        return None;
+    }
+    let index = rand::thread_rng().gen_range(0..samples.len());
+    Some(samples.swap_remove(index))
+}
+
+/// N in a `problem-N.ext` file name.
+fn problem_id(name: &str) -> Option<u32> {
+    Path::new(name)
+        .file_stem()?
+        .to_str()?
+        .strip_prefix("problem-")?
+        .parse()
+        .ok()
+}
+
+/// A single line of real code from `practice_problems.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Snippet {
+    pub id: u32,
+    pub language: String,
+    pub code: String,
+    pub difficulty: String,
+}
+
+#[derive(Deserialize)]
+struct SnippetFile {
+    problems: Vec<Snippet>,
+}
+
+/// The bundled snippets, plus any in `~/.typehero/practice_problems.json`
+/// (which replace bundled snippets with the same id).
+pub fn load_snippets() -> Vec<Snippet> {
+    let mut snippets = parse_snippets(BUNDLED_SNIPPETS);
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(content) = fs::read_to_string(home.join(".typehero/practice_problems.json")) {
+            layer(&mut snippets, parse_snippets(&content), |s| s.id);
+        }
+    }
+    snippets
+}
+
+fn parse_snippets(content: &str) -> Vec<Snippet> {
+    serde_json::from_str::<SnippetFile>(content)
+        .map(|file| file.problems)
+        .unwrap_or_default()
+}
+
+/// A real sentence or passage with its attribution.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Quote {
+    pub id: u32,
+    pub text: String,
+    pub source: String,
+}
+
+impl Quote {
+    pub fn length(&self) -> QuoteLength {
+        match self.text.chars().count() {
+            0..=100 => QuoteLength::Short,
+            101..=250 => QuoteLength::Medium,
+            _ => QuoteLength::Long,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+}
+
+#[derive(Deserialize)]
+struct QuoteFile {
+    quotes: Vec<Quote>,
+}
+
+/// The bundled quotes, plus any in `~/.typehero/quotes/quotes.json` (which
+/// replace bundled quotes with the same id).
+pub fn load_quotes() -> Vec<Quote> {
+    let mut quotes = parse_quotes(BUNDLED_QUOTES);
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(content) = fs::read_to_string(home.join(".typehero/quotes/quotes.json")) {
+            layer(&mut quotes, parse_quotes(&content), |q| q.id);
+        }
+    }
+    quotes
+}
+
+fn parse_quotes(content: &str) -> Vec<Quote> {
+    serde_json::from_str::<QuoteFile>(content)
+        .map(|file| file.quotes)
+        .unwrap_or_default()
+}
+
+/// Pick a random quote, optionally restricted to one length bucket.
+pub fn load_random_quote(length: Option<QuoteLength>) -> Option<Quote> {
+    let mut rng = rand::thread_rng();
+    let quotes: Vec<Quote> = load_quotes()
+        .into_iter()
+        .filter(|q| length.is_none() || length == Some(q.length()))
+        .collect();
 
-    None
+    quotes.choose(&mut rng).cloned()
 }
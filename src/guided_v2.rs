@@ -1,3 +1,4 @@
+use crate::config::Config;
 use crate::display::Display;
 use crate::input::{read_key, InputEvent};
 use rand::seq::SliceRandom;
@@ -32,17 +33,6 @@ impl Finger {
         }
     }
 
-    pub fn color(&self) -> crossterm::style::Color {
-        use crossterm::style::Color;
-        match self {
-            Finger::LeftPinky | Finger::RightPinky => Color::Magenta,
-            Finger::LeftRing | Finger::RightRing => Color::Blue,
-            Finger::LeftMiddle | Finger::RightMiddle => Color::Cyan,
-            Finger::LeftIndex | Finger::RightIndex => Color::Green,
-            Finger::Thumbs => Color::Yellow,
-        }
-    }
-
     pub fn keys(&self) -> Vec<char> {
         match self {
             Finger::LeftPinky => vec!['`', 'q', 'a', 'z'],
@@ -136,10 +126,13 @@ pub struct GuidedPractice {
     current_lesson_idx: usize,
     attempts: Vec<AttemptStats>,
     lesson_stats: HashMap<usize, Vec<AttemptStats>>,
+    mastery_accuracy: f64,
+    mastery_attempts: usize,
+    countdown_step: Duration,
 }
 
 impl GuidedPractice {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
         let lessons = vec![
             // Phase 1: Index fingers (home row anchors)
             Lesson::new(
@@ -249,17 +242,25 @@ impl GuidedPractice {
             current_lesson_idx: 0,
             attempts: Vec::new(),
             lesson_stats: HashMap::new(),
+            mastery_accuracy: config.mastery_accuracy as f64,
+            mastery_attempts: config.mastery_attempts as usize,
+            countdown_step: Duration::from_millis(config.countdown_ms as u64),
         }
     }
 
+    fn is_mastery_attempt(&self, attempt: &AttemptStats) -> bool {
+        attempt.accuracy >= self.mastery_accuracy && attempt.illegal_keys == 0
+    }
+
     fn is_lesson_mastered(&self, lesson_idx: usize) -> bool {
         if let Some(attempts) = self.lesson_stats.get(&lesson_idx) {
-            if attempts.len() < 3 {
+            if attempts.len() < self.mastery_attempts {
                 return false;
             }
 
-            let recent: Vec<&AttemptStats> = attempts.iter().rev().take(3).collect();
-            recent.iter().all(|a| a.accuracy >= 95.0 && a.illegal_keys == 0)
+            let recent: Vec<&AttemptStats> =
+                attempts.iter().rev().take(self.mastery_attempts).collect();
+            recent.iter().all(|a| self.is_mastery_attempt(a))
         } else {
             false
         }
@@ -275,14 +276,17 @@ impl GuidedPractice {
                 let recent = attempts.last().unwrap();
                 if recent.illegal_keys > 0 {
                     format!("Constraint violation - {} illegal keys", recent.illegal_keys)
-                } else if recent.accuracy >= 95.0 {
+                } else if recent.accuracy >= self.mastery_accuracy {
                     let count = attempts
                         .iter()
                         .rev()
-                        .take(3)
-                        .filter(|a| a.accuracy >= 95.0 && a.illegal_keys == 0)
+                        .take(self.mastery_attempts)
+                        .filter(|a| self.is_mastery_attempt(a))
                         .count();
-                    format!("Progress: {}/3 mastery attempts", count)
+                    format!(
+                        "Progress: {}/{} mastery attempts",
+                        count, self.mastery_attempts
+                    )
                 } else {
                     "Keep practicing".to_string()
                 }
@@ -339,6 +343,7 @@ impl GuidedPractice {
                             }
                         }
                         InputEvent::Escape => return Ok(()),
+                        InputEvent::Resize(width, height) => display.resize(width, height),
                         _ => {}
                     }
                 }
@@ -351,7 +356,7 @@ impl GuidedPractice {
             // Countdown before practice
             for countdown in (1..=3).rev() {
                 display.render_countdown(countdown)?;
-                std::thread::sleep(Duration::from_millis(800));
+                std::thread::sleep(self.countdown_step);
             }
 
             let stats = self.practice_lesson(display, lesson, &text)?;
@@ -417,6 +422,7 @@ impl GuidedPractice {
             )?;
 
             match read_key(Duration::from_millis(50))? {
+                InputEvent::Char(_) if display.too_small() => {}
                 InputEvent::Char(ch) => {
                     if current_pos >= chars.len() {
                         continue;
@@ -446,6 +452,7 @@ impl GuidedPractice {
                 InputEvent::Escape => {
                     return Err("Quit early".into());
                 }
+                InputEvent::Resize(width, height) => display.resize(width, height),
                 _ => {}
             }
         }
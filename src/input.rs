@@ -1,5 +1,7 @@
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use std::time::Duration;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
 pub enum InputEvent {
     Char(char),
@@ -8,32 +10,221 @@ pub enum InputEvent {
     Tab,
     Up,
     Down,
+    Left,
+    Right,
     CtrlN,
     CtrlP,
+    /// The terminal was resized to (columns, rows)
+    Resize(u16, u16),
     None,
 }
 
 pub fn read_key(timeout: Duration) -> Result<InputEvent, Box<dyn std::error::Error>> {
     if event::poll(timeout)? {
-        if let Event::Key(KeyEvent {
-            code,
-            modifiers,
-            kind: KeyEventKind::Press,
-            ..
-        }) = event::read()?
-        {
-            return Ok(match code {
-                KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => InputEvent::CtrlN,
-                KeyCode::Char('p') if modifiers.contains(KeyModifiers::CONTROL) => InputEvent::CtrlP,
-                KeyCode::Char(c) => InputEvent::Char(c),
-                KeyCode::Esc => InputEvent::Escape,
-                KeyCode::Enter => InputEvent::Enter,
-                KeyCode::Tab => InputEvent::Tab,
-                KeyCode::Up => InputEvent::Up,
-                KeyCode::Down => InputEvent::Down,
-                _ => InputEvent::None,
-            });
+        match event::read()? {
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                return Ok(match code {
+                    KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => InputEvent::CtrlN,
+                    KeyCode::Char('p') if modifiers.contains(KeyModifiers::CONTROL) => InputEvent::CtrlP,
+                    KeyCode::Char(c) => InputEvent::Char(c),
+                    KeyCode::Esc => InputEvent::Escape,
+                    KeyCode::Enter => InputEvent::Enter,
+                    KeyCode::Tab => InputEvent::Tab,
+                    KeyCode::Up => InputEvent::Up,
+                    KeyCode::Down => InputEvent::Down,
+                    KeyCode::Left => InputEvent::Left,
+                    KeyCode::Right => InputEvent::Right,
+                    _ => InputEvent::None,
+                });
+            }
+            Event::Resize(width, height) => return Ok(InputEvent::Resize(width, height)),
+            _ => {}
         }
     }
     Ok(InputEvent::None)
 }
+
+/// Result of feeding one typed character to a `Composer`.
+pub enum Composed {
+    /// Part of a longer grapheme (dead key, combining mark, emoji sequence)
+    Pending,
+    /// A finished grapheme, NFC-normalised
+    Grapheme(String),
+    /// The pending input can't become the expected grapheme and the character
+    /// doesn't join it: the pending input is finished as typed, and the
+    /// character must be fed again for the next grapheme.
+    Interrupted(String, char),
+}
+
+/// Assembles typed characters into grapheme clusters so they can be compared
+/// with the expected text one grapheme at a time.
+///
+/// Terminals deliver accented input precomposed ("é") or as a spacing dead key
+/// ("´") followed by the base letter, and letters with no precomposed form as
+/// the base letter followed by combining marks. Input that is still on its way
+/// to the expected grapheme is held back instead of being scored; anything
+/// else, like a bare "e" where "é" is expected, is scored straight away. A
+/// dead key followed by the wrong letter or a space is one attempt, scored as
+/// the accented letter or the bare accent.
+#[derive(Default)]
+pub struct Composer {
+    pending: String,
+}
+
+impl Composer {
+    pub fn push(&mut self, ch: char, expected: &str) -> Composed {
+        let mut candidate = self.pending.clone();
+        candidate.push(ch);
+
+        let typed = compose(&candidate);
+        if typed == expected.nfc().collect::<String>() {
+            self.pending.clear();
+            return Composed::Grapheme(typed);
+        }
+        if could_become(&candidate, expected) {
+            self.pending = candidate;
+            return Composed::Pending;
+        }
+        if self.pending.is_empty() || typed.graphemes(true).count() == 1 {
+            self.pending.clear();
+            return Composed::Grapheme(typed);
+        }
+
+        let abandoned = compose(&self.pending);
+        self.pending.clear();
+        Composed::Interrupted(abandoned, ch)
+    }
+}
+
+// Spacing accents sent by dead keys, and the combining mark each one adds.
+// Quotes are left out: they are ordinary text far more often than accents.
+fn dead_key_mark(ch: char) -> Option<char> {
+    Some(match ch {
+        '´' => '\u{301}',
+        '`' => '\u{300}',
+        '^' => '\u{302}',
+        '~' | '˜' => '\u{303}',
+        '¨' => '\u{308}',
+        '¸' => '\u{327}',
+        'ˇ' => '\u{30C}',
+        '˘' => '\u{306}',
+        '˝' => '\u{30B}',
+        '˛' => '\u{328}',
+        '˚' | '°' => '\u{30A}',
+        '˙' => '\u{307}',
+        _ => return None,
+    })
+}
+
+// NFC of the typed characters, with leading dead keys applied to the letter
+// that follows them. A dead key followed by a space is the bare accent.
+fn compose(typed: &str) -> String {
+    let marks: Vec<char> = typed.chars().map_while(dead_key_mark).collect();
+    let rest: String = typed.chars().skip(marks.len()).collect();
+    if marks.is_empty() || rest.is_empty() {
+        return typed.nfc().collect();
+    }
+    if rest == " " {
+        return typed.trim_end().nfc().collect();
+    }
+
+    let mut chars = rest.chars();
+    let base = chars.next().unwrap();
+    std::iter::once(base)
+        .chain(marks)
+        .chain(chars)
+        .nfc()
+        .collect()
+}
+
+fn could_become(typed: &str, expected: &str) -> bool {
+    // Only dead keys so far: wait if the expected grapheme carries their marks
+    if typed.chars().all(|c| dead_key_mark(c).is_some()) {
+        let expected: String = expected.nfd().collect();
+        return typed
+            .chars()
+            .filter_map(dead_key_mark)
+            .all(|mark| expected.contains(mark));
+    }
+
+    // Compared composed, so a base letter is only a prefix of letters with no
+    // precomposed form (and of emoji sequences), never a typo for "é"
+    let expected: String = expected.nfc().collect();
+    let typed = compose(typed);
+    typed.len() < expected.len() && expected.starts_with(&typed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(typed: &str, expected: &str) -> Vec<Composed> {
+        let mut composer = Composer::default();
+        typed
+            .chars()
+            .map(|ch| composer.push(ch, expected))
+            .collect()
+    }
+
+    fn grapheme(composed: &Composed) -> Option<&str> {
+        match composed {
+            Composed::Grapheme(typed) => Some(typed),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn dead_key_and_letter_compose_to_expected() {
+        let results = feed("´e", "é");
+        assert!(matches!(results[0], Composed::Pending));
+        assert_eq!(grapheme(&results[1]), Some("é"));
+    }
+
+    #[test]
+    fn precomposed_input_matches_decomposed_text() {
+        assert_eq!(grapheme(&feed("é", "e\u{301}")[0]), Some("é"));
+    }
+
+    #[test]
+    fn dead_key_and_wrong_letter_are_one_attempt() {
+        let results = feed("´o", "é");
+        assert!(matches!(results[0], Composed::Pending));
+        assert_eq!(grapheme(&results[1]), Some("ó"));
+    }
+
+    #[test]
+    fn dead_key_and_letter_without_precomposed_form_are_one_attempt() {
+        let results = feed("´x", "é");
+        assert_eq!(grapheme(&results[1]), Some("x\u{301}"));
+    }
+
+    #[test]
+    fn dead_key_and_space_are_the_bare_accent() {
+        let results = feed("´ ", "é");
+        assert!(matches!(results[0], Composed::Pending));
+        assert_eq!(grapheme(&results[1]), Some("´"));
+    }
+
+    #[test]
+    fn unrelated_dead_key_is_scored_straight_away() {
+        assert_eq!(grapheme(&feed("`", "é")[0]), Some("`"));
+    }
+
+    #[test]
+    fn base_letter_then_other_letter_interrupts() {
+        let results = feed("ex", "e\u{30A}");
+        assert!(matches!(results[0], Composed::Pending));
+        match &results[1] {
+            Composed::Interrupted(typed, ch) => {
+                assert_eq!(typed, "e");
+                assert_eq!(*ch, 'x');
+            }
+            _ => panic!("expected the pending letter to be interrupted"),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::dictionary::Dictionary;
+use crate::wordlist::WordList;
+use std::sync::OnceLock;
+
+/// A practice language: a frequency-ranked word list (most common first)
+/// and the code its stats are stored under.
+pub struct Language {
+    pub code: &'static str,
+    pub name: &'static str,
+    words: &'static str,
+}
+
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        code: "en",
+        name: "English",
+        words: include_str!("../data/wordlists/common.txt"),
+    },
+    Language {
+        code: "de",
+        name: "Deutsch",
+        words: include_str!("../data/languages/de.txt"),
+    },
+    Language {
+        code: "es",
+        name: "Español",
+        words: include_str!("../data/languages/es.txt"),
+    },
+    Language {
+        code: "fr",
+        name: "Français",
+        words: include_str!("../data/languages/fr.txt"),
+    },
+    Language {
+        code: "pt",
+        name: "Português",
+        words: include_str!("../data/languages/pt.txt"),
+    },
+    Language {
+        code: "pl",
+        name: "Polski",
+        words: include_str!("../data/languages/pl.txt"),
+    },
+];
+
+impl Language {
+    pub fn english() -> &'static Language {
+        &LANGUAGES[0]
+    }
+
+    /// Look a language up by code ("de") or name ("Deutsch"), ignoring case.
+    pub fn find(code_or_name: &str) -> Option<&'static Language> {
+        LANGUAGES.iter().find(|l| {
+            l.code.eq_ignore_ascii_case(code_or_name)
+                || l.name.to_lowercase() == code_or_name.to_lowercase()
+        })
+    }
+
+    pub fn is_english(&self) -> bool {
+        self.code == "en"
+    }
+
+    pub fn wordlist(&self) -> WordList {
+        if self.is_english() {
+            WordList::common()
+        } else {
+            WordList::parse(self.code, self.words)
+        }
+    }
+
+    /// Dictionary for building targeted drills out of real words.
+    pub fn dictionary(&self) -> &'static Dictionary {
+        if self.is_english() {
+            return Dictionary::english();
+        }
+
+        // The packs are small, so build them all the first time one is used
+        static PACKS: OnceLock<Vec<Dictionary>> = OnceLock::new();
+        let packs = PACKS.get_or_init(|| {
+            LANGUAGES
+                .iter()
+                .map(|l| Dictionary::from_words(l.wordlist().words))
+                .collect()
+        });
+        let index = LANGUAGES
+            .iter()
+            .position(|l| l.code == self.code)
+            .unwrap_or(0);
+        &packs[index]
+    }
+}
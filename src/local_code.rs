@@ -0,0 +1,92 @@
+use crate::chunker;
+use crate::exercises::{code_language_key, CodeFilter, CodeSample};
+use ignore::WalkBuilder;
+use rand::seq::SliceRandom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Generated and minified files are rarely worth typing
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Files shorter than this are mostly re-exports and one-line declarations
+const MIN_LINES: usize = 4;
+
+/// A random function-sized chunk of the source files under `root` (or of
+/// `root` itself, if it is a file), skipping anything `.gitignore` excludes.
+/// Only the chosen file is read and chunked, so large trees start quickly.
+/// `None` when no file in the languages the filter allows has a usable chunk.
+pub fn random_sample(
+    root: &Path,
+    filter: &CodeFilter,
+) -> Result<Option<CodeSample>, Box<dyn std::error::Error>> {
+    let mut files = source_files(root)?;
+    if files.is_empty() {
+        return Err(format!("No source files to practice on in {}", root.display()).into());
+    }
+    files.retain(|(_, language)| filter.matches(language, ""));
+
+    let mut rng = rand::thread_rng();
+    files.shuffle(&mut rng);
+    // Files of nothing but short or untypeable chunks are skipped for the next
+    for (path, language) in files {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let chunks: Vec<_> = chunker::chunk(&content, &language)
+            .into_iter()
+            .filter(|chunk| chunk.code.lines().count() >= MIN_LINES && typeable(&chunk.code))
+            .collect();
+        let Some(chunk) = chunks.choose(&mut rng) else {
+            continue;
+        };
+
+        let name = match path.strip_prefix(root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+            _ => path.display().to_string(),
+        };
+        return Ok(Some(CodeSample {
+            code: chunk.code.clone(),
+            language,
+            problem: None,
+            source: Some(format!("{}:{}", name, chunk.line)),
+        }));
+    }
+    if filter.languages.is_empty() {
+        return Err(format!("No source files to practice on in {}", root.display()).into());
+    }
+    Ok(None)
+}
+
+/// Paths of the source files under `root`, each with its extension.
+fn source_files(root: &Path) -> Result<Vec<(PathBuf, String)>, Box<dyn std::error::Error>> {
+    fs::metadata(root).map_err(|e| format!("Could not read {}: {}", root.display(), e))?;
+
+    let mut files = Vec::new();
+    // Honour .gitignore in plain directories too, not just inside a repo
+    for entry in WalkBuilder::new(root).require_git(false).build() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let path = entry.path();
+        let Some(language) = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| code_language_key(ext).is_some())
+        else {
+            continue;
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file())
+            || entry.metadata().map(|m| m.len()).unwrap_or(0) > MAX_FILE_BYTES
+        {
+            continue;
+        }
+        files.push((path.to_path_buf(), language.to_string()));
+    }
+    Ok(files)
+}
+
+/// Whether every character is on a US keyboard.
+fn typeable(code: &str) -> bool {
+    code.chars()
+        .all(|c| c.is_ascii_graphic() || matches!(c, ' ' | '\t' | '\n'))
+}
@@ -1,36 +1,186 @@
+mod chunker;
+mod cli;
 mod code_challenge;
+mod config;
+mod custom_text;
+mod dictionary;
 mod display;
 mod engine;
 mod exercises;
 mod guided_v2;
 mod input;
+mod language;
+mod local_code;
+mod markov;
+mod render;
+mod srs;
 mod stats;
+mod theme;
+mod wordlist;
 
-use code_challenge::CodeChallenge;
+use cli::{Cli, Command};
+use code_challenge::{CodeChallenge, Whitespace};
+use config::Config;
 use crossterm::{
-    execute,
+    cursor, execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use display::Display;
-use engine::{show_summary, TypingSession};
-use exercises::{generate_exercise, load_random_code_sample, ExerciseMode};
+use engine::{show_summary, show_summary_with_notes, TypingSession};
+use exercises::{
+    code_language_name, generate_exercise, load_code_samples, load_random_quote, load_snippets,
+    random_code_sample, AdaptiveSource, CodeFilter, CodeSample, ExerciseMode, Modifiers,
+    QuoteLength, Snippet, CODE_DIFFICULTIES, CODE_LANGUAGES,
+};
 use input::{read_key, InputEvent};
-use stats::Stats;
+use language::{Language, LANGUAGES};
+use markov::MarkovChain;
+use rand::seq::SliceRandom;
+use stats::{SessionSummary, Stats, CODE_LANGUAGE};
 use std::io::stdout;
+use std::ops::RangeInclusive;
 use std::time::Duration;
+use theme::THEMES;
+use wordlist::WordList;
 
-const MENU_OPTIONS: &[&str] = &[
-    "1. Guided Practice (learn proper finger placement)",
-    "2. Random Words (50 words)",
-    "3. Code Patterns (50 patterns)",
-    "4. CodeJam (real programming problems)",
-    "5. Targeted Practice (your problem areas)",
-    "6. Quick Drill (20 words)",
-    "7. View Statistics",
-    "8. Quit",
-];
+/// Main menu entries. Session lengths come from the config.
+fn menu_options(config: &Config) -> Vec<String> {
+    vec![
+        "1. Guided Practice (learn proper finger placement)".to_string(),
+        format!("2. Random Words ({} words)", config.words),
+        format!("3. Code Patterns ({} patterns)", config.words),
+        "4. CodeJam (real programming problems)".to_string(),
+        "5. Code One-Liners (single lines of real code)".to_string(),
+        "6. Targeted Practice (your problem areas)".to_string(),
+        format!("7. Quick Drill ({} words)", config.quick_drill_words),
+        "8. Adaptive Practice (adjusts to your accuracy)".to_string(),
+        "9. Word Lists (choose vocabulary)".to_string(),
+        match &config.custom_text_path {
+            Some(path) => format!("10. Custom Text ({})", path.display()),
+            None => "10. Custom Text (~/.typehero/custom.txt)".to_string(),
+        },
+        "11. Quotes (real sentences and passages)".to_string(),
+        "12. Generated Prose (Markov text from English or your documents)".to_string(),
+        "13. Text Modifiers (capitals, punctuation, numbers)".to_string(),
+        "14. Language (word lists and stats per language)".to_string(),
+        "15. View Statistics".to_string(),
+        "16. Settings (theme, session lengths, mastery)".to_string(),
+        "17. Quit".to_string(),
+    ]
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match cli.command {
+        Command::Help => {
+            println!("{}\n\n{}", env!("CARGO_PKG_DESCRIPTION"), cli::USAGE);
+            return Ok(());
+        }
+        Command::Version => {
+            println!("typehero {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Command::Menu | Command::Text { .. } | Command::Code { .. } => {}
+    }
+
+    // Read the document up front - stdin has to be consumed before raw mode
+    let custom_chunks = match &cli.command {
+        Command::Text { input, options } => {
+            let chunks = custom_text::read_input(input)
+                .map(|raw| custom_text::prepare(&raw, options))
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+            if chunks.is_empty() {
+                eprintln!("No text to practice on");
+                std::process::exit(1);
+            }
+            Some(chunks)
+        }
+        _ => None,
+    };
+
+    let language = match &cli.language {
+        Some(code) => Language::find(code).unwrap_or_else(|| {
+            let codes: Vec<&str> = LANGUAGES.iter().map(|l| l.code).collect();
+            eprintln!(
+                "Unknown language '{}'. Available: {}",
+                code,
+                codes.join(", ")
+            );
+            std::process::exit(1);
+        }),
+        None => Language::english(),
+    };
+
+    let wordlist = match &cli.wordlist {
+        Some(name) => match WordList::find(name) {
+            Ok(list) => list,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        },
+        None => language.wordlist(),
+    };
+
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Pick the CodeJam problem up front too, so a filter that matches
+    // nothing (or a repository that can't be read) is reported on the
+    // command line
+    let code_sample = match &cli.command {
+        Command::Code {
+            languages,
+            difficulties,
+            from,
+        } => {
+            // The saved filters are for the bundled problems
+            let mut filter = match from {
+                Some(_) => CodeFilter::default(),
+                None => config.code.clone(),
+            };
+            if !languages.is_empty() {
+                filter.languages = languages.clone();
+            }
+            if !difficulties.is_empty() {
+                filter.difficulties = difficulties.clone();
+            }
+            let sample = match from {
+                Some(path) => local_code::random_sample(path, &filter).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }),
+                None => random_code_sample(load_code_samples(), &filter),
+            };
+            if sample.is_none() {
+                match from {
+                    Some(path) => {
+                        eprintln!("No code in {} matches: {}", path.display(), filter.label())
+                    }
+                    None => eprintln!("No CodeJam problems match: {}", filter.label()),
+                }
+                std::process::exit(1);
+            }
+            sample
+        }
+        _ => None,
+    };
+
     // Setup terminal
     if let Err(e) = enable_raw_mode() {
         eprintln!("Failed to enable raw mode: {}", e);
@@ -44,22 +194,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
-    let result = run_app();
+    let result = match (custom_chunks, code_sample) {
+        (Some(chunks), _) => Display::new(config.theme()).and_then(|display| {
+            let mut stats = Stats::load();
+            stats.set_language(language.code);
+            run_custom_text(&display, &mut stats, &chunks, &config)
+        }),
+        (None, Some(sample)) => Display::new(config.theme()).and_then(|display| {
+            let mut stats = Stats::load();
+            CodeChallenge::new(sample)
+                .with_whitespace(config.code_whitespace)
+                .run(&display, &mut stats)
+        }),
+        (None, None) => run_app(wordlist, language, config),
+    };
 
     // Cleanup terminal
     let _ = disable_raw_mode();
-    let _ = execute!(stdout(), LeaveAlternateScreen);
+    let _ = execute!(stdout(), LeaveAlternateScreen, cursor::Show);
 
     result
 }
 
-fn run_app() -> Result<(), Box<dyn std::error::Error>> {
-    let display = Display::new()?;
+fn run_app(
+    mut wordlist: WordList,
+    mut language: &'static Language,
+    mut config: Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let display = Display::new(config.theme())?;
     let mut stats = Stats::load();
+    stats.set_language(language.code);
+    let mut modifiers = Modifiers::default();
     let mut selected = 0;
 
     loop {
-        display.render_menu(selected, MENU_OPTIONS)?;
+        let options = menu_options(&config);
+        display.render_menu(selected, &options)?;
 
         match read_key(Duration::from_millis(50))? {
             InputEvent::Up => {
@@ -68,7 +238,7 @@ fn run_app() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             InputEvent::Down => {
-                if selected < MENU_OPTIONS.len() - 1 {
+                if selected < options.len() - 1 {
                     selected += 1;
                 }
             }
@@ -76,72 +246,193 @@ fn run_app() -> Result<(), Box<dyn std::error::Error>> {
                 match selected {
                     0 => {
                         // Guided Practice
-                        let mut guided = guided_v2::GuidedPractice::new();
+                        let mut guided = guided_v2::GuidedPractice::new(&config);
                         let _ = guided.run(&display);
                     }
                     1 => {
                         // Random Words
-                        let text = generate_exercise(&ExerciseMode::RandomWords, 50);
-                        let mut session = TypingSession::new(text, stats.clone());
+                        let text = generate_exercise(
+                            &ExerciseMode::Words(wordlist.clone()),
+                            config.words as usize,
+                        );
+                        let text = modifiers.apply(&text);
+                        let mut session = TypingSession::new(text, stats.clone())
+                            .with_modifiers(modifiers)
+                            .with_quit_warning(config.early_quit_warning);
                         let summary = session.run(&display)?;
                         stats = session.stats().clone();
                         show_summary(&display, &summary, &stats)?;
                     }
                     2 => {
-                        // Code Patterns
-                        let text = generate_exercise(&ExerciseMode::Code, 50);
-                        let mut session = TypingSession::new(text, stats.clone());
+                        // Code Patterns, recorded under the code stats
+                        let language = stats.language().to_string();
+                        stats.set_language(CODE_LANGUAGE);
+                        let text = generate_exercise(&ExerciseMode::Code, config.words as usize);
+                        let mut session = TypingSession::new(text, stats.clone())
+                            .with_mode("code-patterns")
+                            .with_quit_warning(config.early_quit_warning);
                         let summary = session.run(&display)?;
                         stats = session.stats().clone();
                         show_summary(&display, &summary, &stats)?;
+                        stats.set_language(&language);
                     }
                     3 => {
                         // CodeJam
-                        if let Some(sample) = load_random_code_sample() {
-                            let mut challenge = CodeChallenge::new(sample.code, sample.language);
-                            let _ = challenge.run(&display, &stats);
-                        } else {
-                            // Fallback to code patterns if no samples found
-                            let text = generate_exercise(&ExerciseMode::Code, 50);
-                            let mut session = TypingSession::new(text, stats.clone());
-                            let summary = session.run(&display)?;
-                            stats = session.stats().clone();
-                            show_summary(&display, &summary, &stats)?;
+                        let samples = load_code_samples();
+                        if let Some(sample) = choose_code_sample(&display, samples, &mut config)? {
+                            let mut challenge =
+                                CodeChallenge::new(sample).with_whitespace(config.code_whitespace);
+                            let _ = challenge.run(&display, &mut stats);
                         }
                     }
                     4 => {
-                        // Targeted Practice
-                        let slowest = stats.slowest_bigrams(10);
-                        if slowest.is_empty() {
-                            // Fall back to random words if no data yet
-                            let text = generate_exercise(&ExerciseMode::RandomWords, 50);
-                            let mut session = TypingSession::new(text, stats.clone());
+                        // Code One-Liners
+                        run_one_liners(&display, &mut stats, &mut config)?;
+                    }
+                    5 => {
+                        // Targeted Practice - spaced repetition over weak bigrams/keys
+                        stats.enroll_weak_items(10, 5);
+                        let due = stats.due_srs_items(10);
+                        if due.is_empty() {
+                            // Nothing due yet (or no data) - fall back to random words
+                            let text = generate_exercise(
+                                &ExerciseMode::Words(language.wordlist()),
+                                config.words as usize,
+                            );
+                            let text = modifiers.apply(&text);
+                            let mut session = TypingSession::new(text, stats.clone())
+                                .with_modifiers(modifiers)
+                                .with_quit_warning(config.early_quit_warning);
                             let summary = session.run(&display)?;
                             stats = session.stats().clone();
                             show_summary(&display, &summary, &stats)?;
                         } else {
-                            let bigrams: Vec<String> =
-                                slowest.iter().map(|(b, _)| b.clone()).collect();
-                            let text = generate_exercise(&ExerciseMode::Targeted(bigrams), 50);
-                            let mut session = TypingSession::new(text, stats.clone());
+                            let patterns: Vec<String> =
+                                due.iter().map(|item| item.pattern.clone()).collect();
+                            let text = generate_exercise(
+                                &ExerciseMode::Targeted(patterns, language),
+                                config.words as usize,
+                            );
+                            let text = modifiers.apply(&text);
+                            let mut session = TypingSession::new(text, stats.clone())
+                                .with_modifiers(modifiers)
+                                .with_quit_warning(config.early_quit_warning);
                             let summary = session.run(&display)?;
                             stats = session.stats().clone();
+                            stats.review_srs_items(
+                                &due,
+                                session.key_samples(),
+                                session.bigram_samples(),
+                            );
                             show_summary(&display, &summary, &stats)?;
                         }
                     }
-                    5 => {
+                    6 => {
                         // Quick Drill
-                        let text = generate_exercise(&ExerciseMode::RandomWords, 20);
-                        let mut session = TypingSession::new(text, stats.clone());
+                        let text = generate_exercise(
+                            &ExerciseMode::Words(wordlist.clone()),
+                            config.quick_drill_words as usize,
+                        );
+                        let text = modifiers.apply(&text);
+                        let mut session = TypingSession::new(text, stats.clone())
+                            .with_modifiers(modifiers)
+                            .with_quit_warning(config.early_quit_warning);
                         let summary = session.run(&display)?;
                         stats = session.stats().clone();
                         show_summary(&display, &summary, &stats)?;
                     }
-                    6 => {
+                    7 => {
+                        // Adaptive Practice
+                        let bigrams: Vec<String> = stats
+                            .slowest_bigrams(10)
+                            .into_iter()
+                            .map(|(b, _)| b)
+                            .collect();
+                        let source = AdaptiveSource::new(
+                            bigrams,
+                            language,
+                            stats.average_bigram_ms(),
+                            config.words as usize,
+                            modifiers,
+                        );
+                        let mut session =
+                            TypingSession::with_source(Box::new(source), stats.clone())
+                                .with_quit_warning(config.early_quit_warning)
+                                .with_modifiers(modifiers);
+                        let summary = session.run(&display)?;
+                        stats = session.stats().clone();
+                        show_summary(&display, &summary, &stats)?;
+                    }
+                    8 => {
+                        // Word Lists
+                        if let Some(list) = choose_wordlist(&display, &wordlist.name)? {
+                            wordlist = list;
+                        }
+                    }
+                    9 => {
+                        // Custom Text
+                        let path = config.custom_text_path();
+                        match path.as_deref().map(custom_text::read_file) {
+                            Some(Ok(raw)) => {
+                                let chunks = custom_text::prepare(&raw, &config.custom_text);
+                                run_custom_text(&display, &mut stats, &chunks, &config)?;
+                            }
+                            _ => {
+                                let path = path
+                                    .map(|p| p.display().to_string())
+                                    .unwrap_or_else(|| "~/.typehero/custom.txt".to_string());
+                                show_message(
+                                    &display,
+                                    "Custom Text",
+                                    &[
+                                        format!("No text found at {}", path),
+                                        String::new(),
+                                        "Save a document there, or run:".to_string(),
+                                        "  typehero text <file>".to_string(),
+                                        "  some-command | typehero text -".to_string(),
+                                    ],
+                                )?;
+                            }
+                        }
+                    }
+                    10 => {
+                        // Quotes
+                        if let Some(length) = choose_quote_length(&display)? {
+                            run_quote(&display, &stats, length, &config)?;
+                        }
+                    }
+                    11 => {
+                        // Generated Prose
+                        if let Some((mode, prose_stats)) = choose_prose(&display, &stats, language)?
+                        {
+                            let text = generate_exercise(&mode, config.words as usize);
+                            let mut session = TypingSession::new(text, prose_stats)
+                                .with_quit_warning(config.early_quit_warning);
+                            let summary = session.run(&display)?;
+                            show_summary(&display, &summary, session.stats())?;
+                        }
+                    }
+                    12 => {
+                        // Text Modifiers
+                        choose_modifiers(&display, &mut modifiers)?;
+                    }
+                    13 => {
+                        // Language
+                        if let Some(choice) = choose_language(&display, language)? {
+                            language = choice;
+                            wordlist = language.wordlist();
+                            stats.set_language(language.code);
+                        }
+                    }
+                    14 => {
                         // View Statistics
                         show_stats(&display, &stats)?;
                     }
-                    7 => {
+                    15 => {
+                        // Settings
+                        choose_settings(&display, &mut config)?;
+                    }
+                    16 => {
                         // Quit
                         break;
                     }
@@ -149,66 +440,822 @@ fn run_app() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
             InputEvent::Escape => break,
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Practice a document chunk by chunk, stopping if the user quits a chunk.
+fn run_custom_text(
+    display: &Display,
+    stats: &mut Stats,
+    chunks: &[String],
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for chunk in chunks {
+        let text = generate_exercise(&ExerciseMode::Custom(chunk.clone()), 0);
+        let mut session =
+            TypingSession::new(text, stats.clone()).with_quit_warning(config.early_quit_warning);
+        let summary = session.run(display)?;
+        *stats = session.stats().clone();
+        show_summary(display, &summary, stats)?;
+
+        if !session.is_complete() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Type one random quote, then show its attribution and personal best.
+fn run_quote(
+    display: &Display,
+    stats: &Stats,
+    length: Option<QuoteLength>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(quote) = load_random_quote(length) else {
+        return show_message(
+            display,
+            "Quotes",
+            &["No quote matches the chosen length.".to_string()],
+        );
+    };
+
+    // The corpus is English, so keep its keystrokes out of other languages
+    let mut stats = stats.clone();
+    stats.set_language(Language::english().code);
+
+    let text = generate_exercise(&ExerciseMode::Custom(quote.text.clone()), 0);
+    let mut session =
+        TypingSession::new(text, stats.clone()).with_quit_warning(config.early_quit_warning);
+    let summary = session.run(display)?;
+    stats = session.stats().clone();
+
+    let mut notes = vec![format!("— {}", quote.source), String::new()];
+    notes.extend(personal_best_note(
+        &mut stats,
+        "quote",
+        &quote.id.to_string(),
+        session.is_complete(),
+        &summary,
+    ));
+
+    show_summary_with_notes(display, &summary, &stats, &notes)
+}
+
+/// Record a completed run as a personal best for one item (kind "quote",
+/// "snippet", ...) and describe how it compares.
+fn personal_best_note(
+    stats: &mut Stats,
+    kind: &str,
+    id: &str,
+    complete: bool,
+    summary: &SessionSummary,
+) -> Option<String> {
+    let previous = stats.personal_best(kind, id);
+    if !complete {
+        Some(format!("Finish the {} to set a personal best.", kind))
+    } else if stats.record_personal_best(kind, id, summary) {
+        Some(match previous {
+            Some(best) => format!(
+                "New personal best for this {}! (was {:.1} adj WPM)",
+                kind, best.adjusted_wpm
+            ),
+            None => format!("First run of this {} - personal best set.", kind),
+        })
+    } else {
+        previous.map(|best| {
+            format!(
+                "Personal best for this {}: {:.1} adj WPM ({:.1}% acc, {})",
+                kind,
+                best.adjusted_wpm,
+                best.accuracy,
+                best.timestamp.format("%Y-%m-%d")
+            )
+        })
+    }
+}
+
+/// Type one-line snippets of real code, one session each, picking a random
+/// snippet that matches the filters every time. Keystrokes go under the
+/// `code` stats rather than the active language's.
+fn run_one_liners(
+    display: &Display,
+    stats: &mut Stats,
+    config: &mut Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let language = stats.language().to_string();
+    stats.set_language(CODE_LANGUAGE);
+    let result = practice_one_liners(display, stats, config);
+    stats.set_language(&language);
+    result
+}
+
+fn practice_one_liners(
+    display: &Display,
+    stats: &mut Stats,
+    config: &mut Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let snippets = load_snippets();
+    while let Some(snippet) = choose_snippet(display, &snippets, config)? {
+        let text = generate_exercise(&ExerciseMode::Custom(snippet.code.clone()), 0);
+        let mut session = TypingSession::new(text, stats.clone())
+            .with_mode("one-liner")
+            .with_quit_warning(config.early_quit_warning);
+        let summary = session.run(display)?;
+        *stats = session.stats().clone();
+
+        let mut notes = vec![
+            format!(
+                "— {}, {} (snippet #{})",
+                code_language_name(&snippet.language),
+                snippet.difficulty,
+                snippet.id
+            ),
+            String::new(),
+        ];
+        notes.extend(personal_best_note(
+            stats,
+            "snippet",
+            &snippet.id.to_string(),
+            session.is_complete(),
+            &summary,
+        ));
+        show_summary_with_notes(display, &summary, stats, &notes)?;
+    }
+    Ok(())
+}
+
+/// Start a random snippet that matches the code filters, or change them.
+/// Returns None when the user backs out.
+fn choose_snippet(
+    display: &Display,
+    snippets: &[Snippet],
+    config: &mut Config,
+) -> Result<Option<Snippet>, Box<dyn std::error::Error>> {
+    let mut selected = 0;
+
+    loop {
+        let matching: Vec<&Snippet> = snippets
+            .iter()
+            .filter(|s| config.code.matches(&s.language, &s.difficulty))
+            .collect();
+        let options = [
+            format!("Random snippet ({} match)", matching.len()),
+            format!("Filters: {}", config.code.label()),
+        ];
+        display.render_list(
+            "Code One-Liners",
+            selected,
+            &options,
+            "Enter to select, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < options.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') if selected == 1 => {
+                choose_code_filter(display, config)?;
+            }
+            InputEvent::Enter | InputEvent::Char(' ') => {
+                if let Some(snippet) = matching.choose(&mut rand::thread_rng()) {
+                    return Ok(Some((*snippet).clone()));
+                }
+            }
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+fn choose_quote_length(
+    display: &Display,
+) -> Result<Option<Option<QuoteLength>>, Box<dyn std::error::Error>> {
+    let choices = [
+        ("Any length", None),
+        ("Short (up to 100 characters)", Some(QuoteLength::Short)),
+        ("Medium (100-250 characters)", Some(QuoteLength::Medium)),
+        ("Long passages (250+ characters)", Some(QuoteLength::Long)),
+    ];
+    let options: Vec<&str> = choices.iter().map(|(label, _)| *label).collect();
+    let mut selected = 0;
+
+    loop {
+        display.render_list(
+            "Quotes",
+            selected,
+            &options,
+            "Enter to start, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < choices.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => return Ok(Some(choices[selected].1)),
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+/// Pick a corpus for generated prose, and whether to lean on weak bigrams.
+/// Returns the exercise and the stats to record it under: the bundled
+/// corpus is English, user documents count toward the active language.
+fn choose_prose(
+    display: &Display,
+    stats: &Stats,
+    language: &'static Language,
+) -> Result<Option<(ExerciseMode, Stats)>, Box<dyn std::error::Error>> {
+    let corpus_dir = MarkovChain::user_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| "~/.typehero/corpus".to_string());
+    let options = [
+        "Bundled English".to_string(),
+        "Bundled English, weighted toward your slowest bigrams".to_string(),
+        format!("Your documents ({})", corpus_dir),
+        "Your documents, weighted toward your slowest bigrams".to_string(),
+    ];
+    let mut selected = 0;
+
+    loop {
+        display.render_list(
+            "Generated Prose",
+            selected,
+            &options,
+            "Enter to start, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < options.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => {
+                let mut stats = stats.clone();
+                let chain = if selected < 2 {
+                    stats.set_language(Language::english().code);
+                    MarkovChain::english()
+                } else if let Some(chain) = MarkovChain::user() {
+                    stats.set_language(language.code);
+                    chain
+                } else {
+                    show_message(
+                        display,
+                        "Generated Prose",
+                        &[
+                            format!("No documents found in {}", corpus_dir),
+                            String::new(),
+                            "Add .txt or .md files there to generate prose in your own style."
+                                .to_string(),
+                        ],
+                    )?;
+                    continue;
+                };
+
+                let weak_bigrams = if selected % 2 == 1 {
+                    stats
+                        .slowest_bigrams(10)
+                        .into_iter()
+                        .map(|(b, _)| b)
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let mode = ExerciseMode::Prose {
+                    chain,
+                    weak_bigrams,
+                };
+                return Ok(Some((mode, stats)));
+            }
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+fn show_message(
+    display: &Display,
+    title: &str,
+    lines: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    display.render_message(title, lines)?;
+
+    // Wait for any key
+    loop {
+        match read_key(Duration::from_millis(100))? {
+            InputEvent::Char(_) | InputEvent::Enter | InputEvent::Escape => break,
+            InputEvent::Resize(width, height) => {
+                display.resize(width, height);
+                display.render_message(title, lines)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick a CodeJam problem from those matching the code filters, or a
+/// random one.
+fn choose_code_sample(
+    display: &Display,
+    mut samples: Vec<CodeSample>,
+    config: &mut Config,
+) -> Result<Option<CodeSample>, Box<dyn std::error::Error>> {
+    let mut selected = 0;
+
+    loop {
+        let matching: Vec<usize> = (0..samples.len())
+            .filter(|&i| {
+                let sample = &samples[i];
+                config.code.matches(&sample.language, sample.difficulty())
+            })
+            .collect();
+        let mut options = vec![
+            format!("Random problem ({} match)", matching.len()),
+            format!("Filters: {}", config.code.label()),
+        ];
+        options.extend(matching.iter().map(|&i| {
+            let sample = &samples[i];
+            let language = code_language_name(&sample.language);
+            match &sample.problem {
+                Some(problem) => {
+                    let mut entry = format!(
+                        "{:>3}. {:<6} {:<10} {}",
+                        problem.id, problem.difficulty, language, problem.statement
+                    );
+                    if let Some(part) = &sample.source {
+                        entry.push_str(&format!(" ({})", part));
+                    }
+                    entry
+                }
+                None => format!("     {:<6} {:<10} (no problem statement)", "", language),
+            }
+        }));
+
+        display.render_list(
+            "CodeJam - choose a problem",
+            selected,
+            &options,
+            "Enter to select, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < options.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => match selected {
+                0 => {
+                    if let Some(&index) = matching.choose(&mut rand::thread_rng()) {
+                        return Ok(Some(samples.swap_remove(index)));
+                    }
+                }
+                1 => choose_code_filter(display, config)?,
+                n => return Ok(Some(samples.swap_remove(matching[n - 2]))),
+            },
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+/// Tick the languages and difficulty tiers to practice in CodeJam and Code
+/// One-Liners. Changes are saved when leaving the screen.
+fn choose_code_filter(
+    display: &Display,
+    config: &mut Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved = config.clone();
+    let mut selected = 0;
+
+    loop {
+        let check = |list: &[String], item: &str| {
+            if list.iter().any(|l| l == item) {
+                "[x]"
+            } else {
+                "[ ]"
+            }
+        };
+        let options: Vec<String> = CODE_LANGUAGES
+            .iter()
+            .map(|l| {
+                format!(
+                    "{} {}",
+                    check(&config.code.languages, l),
+                    code_language_name(l)
+                )
+            })
+            .chain(
+                CODE_DIFFICULTIES
+                    .iter()
+                    .map(|d| format!("{} {}", check(&config.code.difficulties, d), d)),
+            )
+            .collect();
+        display.render_list(
+            "Code Filters - languages, then difficulty",
+            selected,
+            &options,
+            "Enter/Space to toggle (none ticked allows all), ESC to save and go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < options.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => {
+                match selected.checked_sub(CODE_LANGUAGES.len()) {
+                    None => toggle(
+                        &mut config.code.languages,
+                        CODE_LANGUAGES[selected],
+                        CODE_LANGUAGES,
+                    ),
+                    Some(i) => toggle(
+                        &mut config.code.difficulties,
+                        CODE_DIFFICULTIES[i],
+                        CODE_DIFFICULTIES,
+                    ),
+                }
+            }
+            InputEvent::Escape => break,
+            InputEvent::Resize(width, height) => display.resize(width, height),
             _ => {}
         }
     }
 
+    if *config != saved {
+        save_config(display, config)?;
+    }
     Ok(())
 }
 
+/// Add or remove `item`, keeping the list in the order of `all`.
+fn toggle(list: &mut Vec<String>, item: &str, all: &[&str]) {
+    let on = !list.iter().any(|l| l == item);
+    *list = all
+        .iter()
+        .filter(|&&entry| {
+            if entry == item {
+                on
+            } else {
+                list.iter().any(|l| l == entry)
+            }
+        })
+        .map(|entry| entry.to_string())
+        .collect();
+}
+
+/// Write the config, telling the user if that fails. The changes still
+/// apply until they quit.
+fn save_config(display: &Display, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    match config.save() {
+        Ok(()) => Ok(()),
+        Err(e) => show_message(
+            display,
+            "Settings",
+            &[
+                "Your settings apply until you quit, but couldn't be saved:".to_string(),
+                e.to_string(),
+            ],
+        ),
+    }
+}
+
+fn choose_wordlist(
+    display: &Display,
+    active: &str,
+) -> Result<Option<WordList>, Box<dyn std::error::Error>> {
+    let lists = WordList::discover();
+    let options: Vec<String> = lists
+        .iter()
+        .map(|list| {
+            format!(
+                "{} {} ({} words)",
+                if list.name == active { "✓" } else { " " },
+                list.name,
+                list.words.len()
+            )
+        })
+        .collect();
+    let hint = match WordList::user_dir() {
+        Some(dir) => format!(
+            "Enter to use for Random Words / Quick Drill, ESC to go back. Add lists to {}",
+            dir.display()
+        ),
+        None => "Enter to use for Random Words / Quick Drill, ESC to go back".to_string(),
+    };
+    let mut selected = lists.iter().position(|l| l.name == active).unwrap_or(0);
+
+    loop {
+        display.render_list("Word Lists", selected, &options, &hint)?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < lists.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => {
+                return Ok(lists.into_iter().nth(selected));
+            }
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+fn choose_language(
+    display: &Display,
+    active: &'static Language,
+) -> Result<Option<&'static Language>, Box<dyn std::error::Error>> {
+    let options: Vec<String> = LANGUAGES
+        .iter()
+        .map(|l| {
+            format!(
+                "{} {} ({})",
+                if l.code == active.code { "✓" } else { " " },
+                l.name,
+                l.code
+            )
+        })
+        .collect();
+    let mut selected = LANGUAGES
+        .iter()
+        .position(|l| l.code == active.code)
+        .unwrap_or(0);
+
+    loop {
+        display.render_list(
+            "Language",
+            selected,
+            &options,
+            "Enter to switch word lists and stats to this language, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < LANGUAGES.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => return Ok(Some(&LANGUAGES[selected])),
+            InputEvent::Escape => return Ok(None),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+fn choose_modifiers(
+    display: &Display,
+    modifiers: &mut Modifiers,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut selected = 0;
+
+    loop {
+        let check = |on: bool| if on { "[x]" } else { "[ ]" };
+        let options = [
+            format!("{} Capitalise sentences", check(modifiers.capitalize)),
+            format!(
+                "{} Punctuation (commas, periods, quotes, parentheses)",
+                check(modifiers.punctuation)
+            ),
+            format!(
+                "{} Numbers (years, decimals, IDs)",
+                check(modifiers.numbers)
+            ),
+        ];
+        display.render_list(
+            "Text Modifiers - applied to word-based modes",
+            selected,
+            &options,
+            "Enter/Space to toggle, ESC to go back",
+        )?;
+
+        match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => selected = selected.saturating_sub(1),
+            InputEvent::Down if selected + 1 < options.len() => selected += 1,
+            InputEvent::Enter | InputEvent::Char(' ') => match selected {
+                0 => modifiers.capitalize = !modifiers.capitalize,
+                1 => modifiers.punctuation = !modifiers.punctuation,
+                _ => modifiers.numbers = !modifiers.numbers,
+            },
+            InputEvent::Escape => return Ok(()),
+            InputEvent::Resize(width, height) => display.resize(width, height),
+            _ => {}
+        }
+    }
+}
+
+/// Edit the config with ←/→; changes are saved when leaving the screen.
+fn choose_settings(
+    display: &Display,
+    config: &mut Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let saved = config.clone();
+    let mut selected = 0;
+
+    loop {
+        let options = [
+            format!("Theme                      < {} >", config.theme),
+            format!("Words per session          < {} >", config.words),
+            format!(
+                "Quick Drill words          < {} >",
+                config.quick_drill_words
+            ),
+            format!(
+                "Mastery accuracy           < {}% >",
+                config.mastery_accuracy
+            ),
+            format!("Mastery attempts in a row  < {} >", config.mastery_attempts),
+            format!("Countdown step             < {} ms >", config.countdown_ms),
+            format!(
+                "Warn when quitting before  < {}% >",
+                config.early_quit_warning
+            ),
+            format!(
+                "CodeJam whitespace         < {} >",
+                config.code_whitespace.name()
+            ),
+        ];
+        let hint = if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            "←/→ to change, ESC to save and go back (NO_COLOR is set, so colours stay off)"
+        } else {
+            "←/→ to change, ESC to save and go back"
+        };
+        display.render_list("Settings", selected, &options, hint)?;
+
+        let step = match read_key(Duration::from_millis(50))? {
+            InputEvent::Up => {
+                selected = selected.saturating_sub(1);
+                continue;
+            }
+            InputEvent::Down if selected + 1 < options.len() => {
+                selected += 1;
+                continue;
+            }
+            InputEvent::Left => -1,
+            InputEvent::Right | InputEvent::Enter | InputEvent::Char(' ') => 1,
+            InputEvent::Escape => break,
+            InputEvent::Resize(width, height) => {
+                display.resize(width, height);
+                continue;
+            }
+            _ => continue,
+        };
+
+        match selected {
+            0 => {
+                let current = THEMES
+                    .iter()
+                    .position(|t| t.name.eq_ignore_ascii_case(&config.theme))
+                    .unwrap_or(0);
+                let next = (current as i64 + step).rem_euclid(THEMES.len() as i64);
+                config.theme = THEMES[next as usize].name.to_string();
+                display.set_theme(config.theme());
+            }
+            1 => config.words = nudge(config.words, step * 10, config::WORDS),
+            2 => {
+                config.quick_drill_words = nudge(
+                    config.quick_drill_words,
+                    step * 5,
+                    config::QUICK_DRILL_WORDS,
+                )
+            }
+            3 => {
+                config.mastery_accuracy =
+                    nudge(config.mastery_accuracy, step, config::MASTERY_ACCURACY)
+            }
+            4 => {
+                config.mastery_attempts =
+                    nudge(config.mastery_attempts, step, config::MASTERY_ATTEMPTS)
+            }
+            5 => config.countdown_ms = nudge(config.countdown_ms, step * 100, config::COUNTDOWN_MS),
+            6 => {
+                config.early_quit_warning = nudge(
+                    config.early_quit_warning,
+                    step * 10,
+                    config::EARLY_QUIT_WARNING,
+                )
+            }
+            _ => {
+                let modes = Whitespace::ALL;
+                let current = modes
+                    .iter()
+                    .position(|&m| m == config.code_whitespace)
+                    .unwrap_or(0);
+                let next = (current as i64 + step).rem_euclid(modes.len() as i64);
+                config.code_whitespace = modes[next as usize];
+            }
+        }
+    }
+
+    if *config != saved {
+        save_config(display, config)?;
+    }
+    Ok(())
+}
+
+/// Move a setting by `step`, staying within its accepted range.
+fn nudge(value: u32, step: i64, range: RangeInclusive<u32>) -> u32 {
+    (value as i64 + step).clamp(*range.start() as i64, *range.end() as i64) as u32
+}
+
 fn show_stats(display: &Display, stats: &Stats) -> Result<(), Box<dyn std::error::Error>> {
-    display.clear()?;
+    // Tab flips between the active language's stats and code practice's
+    let mut view = stats.clone();
+    render_stats(display, &view)?;
+
+    // Wait for any key
+    loop {
+        match read_key(Duration::from_millis(100))? {
+            InputEvent::Char(_) | InputEvent::Enter | InputEvent::Escape => break,
+            InputEvent::Tab => {
+                if view.language() == CODE_LANGUAGE {
+                    view.set_language(stats.language());
+                } else {
+                    view.set_language(CODE_LANGUAGE);
+                }
+                render_stats(display, &view)?;
+            }
+            InputEvent::Resize(width, height) => {
+                display.resize(width, height);
+                render_stats(display, &view)?;
+            }
+            _ => {}
+        }
+    }
 
-    use crossterm::{cursor, style::Print, QueueableCommand};
-    use std::io::{stdout, Write};
+    Ok(())
+}
+
+fn render_stats(display: &Display, stats: &Stats) -> Result<(), Box<dyn std::error::Error>> {
+    if display.too_small() {
+        return display.render_too_small();
+    }
 
-    let mut out = stdout();
+    let mut frame = display.frame();
     let mut row = 0;
 
-    // Title
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print("═══ Lifetime Statistics ═══"))?;
+    // Title - stats are per language, with code practice kept apart
+    let (title, other) = if stats.language() == CODE_LANGUAGE {
+        ("Code", "language")
+    } else {
+        let language = Language::find(stats.language()).unwrap_or(Language::english());
+        (language.name, "code")
+    };
+    frame
+        .move_to(2, row)
+        .print(format!("═══ Lifetime Statistics ({}) ═══", title));
     row += 2;
 
     // Overall stats
     let sessions = stats.sessions();
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print(format!("Total sessions: {}", sessions.len())))?;
+    frame
+        .move_to(2, row)
+        .print(format!("Total sessions: {}", sessions.len()));
     row += 1;
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print(format!("Total keystrokes: {}", stats.total_keys_public())))?;
+    frame
+        .move_to(2, row)
+        .print(format!("Total keystrokes: {}", stats.total_keys_public()));
     row += 1;
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print(format!("Total errors: {}", stats.total_errors_public())))?;
+    frame
+        .move_to(2, row)
+        .print(format!("Total errors: {}", stats.total_errors_public()));
     row += 1;
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print(format!(
-            "Lifetime accuracy: {:.1}%",
-            stats.lifetime_accuracy()
-        )))?;
+    frame.move_to(2, row).print(format!(
+        "Lifetime accuracy: {:.1}%",
+        stats.lifetime_accuracy()
+    ));
     row += 1;
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print(format!(
-            "Average adjusted WPM: {:.1}",
-            stats.average_adjusted_wpm()
-        )))?;
+    frame.move_to(2, row).print(format!(
+        "Average adjusted WPM: {:.1}",
+        stats.average_adjusted_wpm()
+    ));
     row += 2;
 
     // Recent sessions
     if !sessions.is_empty() {
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print("Recent sessions (last 5):"))?;
+        frame.move_to(2, row).print("Recent sessions (last 5):");
         row += 1;
 
         let recent = sessions.iter().take(5);
         for session in recent {
-            out.queue(cursor::MoveTo(4, row))?.queue(Print(format!(
-                "{} - {:.1} WPM ({:.1}% acc) → {:.1} adj WPM",
+            let tags: Vec<&str> = [session.mode.as_str(), session.modifiers.as_str()]
+                .into_iter()
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", tags.join(", "))
+            };
+            frame.move_to(4, row).print(format!(
+                "{} - {:.1} WPM ({:.1}% acc) → {:.1} adj WPM{}",
                 session.timestamp.format("%Y-%m-%d %H:%M"),
                 session.raw_wpm,
                 session.accuracy,
-                session.adjusted_wpm
-            )))?;
+                session.adjusted_wpm,
+                tags
+            ));
             row += 1;
         }
         row += 1;
@@ -217,12 +1264,12 @@ fn show_stats(display: &Display, stats: &Stats) -> Result<(), Box<dyn std::error
     // Problem areas
     let slowest = stats.slowest_bigrams(5);
     if !slowest.is_empty() {
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print("Slowest bigrams:"))?;
+        frame.move_to(2, row).print("Slowest bigrams:");
         row += 1;
         for (bigram, ms) in slowest {
-            out.queue(cursor::MoveTo(4, row))?
-                .queue(Print(format!("  '{}' - {:.0}ms", bigram, ms)))?;
+            frame
+                .move_to(4, row)
+                .print(format!("  '{}' - {:.0}ms", bigram, ms));
             row += 1;
         }
         row += 1;
@@ -230,30 +1277,39 @@ fn show_stats(display: &Display, stats: &Stats) -> Result<(), Box<dyn std::error
 
     let error_prone = stats.most_error_prone_keys(5);
     if !error_prone.is_empty() {
-        out.queue(cursor::MoveTo(2, row))?
-            .queue(Print("Most error-prone keys:"))?;
+        frame.move_to(2, row).print("Most error-prone keys:");
         row += 1;
         for (key, rate) in error_prone {
-            out.queue(cursor::MoveTo(4, row))?
-                .queue(Print(format!("  '{}' - {:.1}% error rate", key, rate)))?;
+            frame
+                .move_to(4, row)
+                .print(format!("  '{}' - {:.1}% error rate", key, rate));
             row += 1;
         }
+        row += 1;
     }
 
-    row += 2;
-    out.queue(cursor::MoveTo(2, row))?
-        .queue(Print("Press any key to return..."))?;
-
-    out.flush()?;
-
-    // Wait for any key
-    loop {
-        if let InputEvent::Char(_) | InputEvent::Enter | InputEvent::Escape =
-            read_key(Duration::from_millis(100))?
-        {
-            break;
-        }
+    // Spaced repetition schedule
+    let srs_items = stats.srs_items();
+    if !srs_items.is_empty() {
+        let now = chrono::Utc::now();
+        let due = srs_items.iter().filter(|item| item.is_due(now)).count();
+        let graduated = srs_items
+            .iter()
+            .filter(|item| item.interval_days >= 21.0)
+            .count();
+        frame.move_to(2, row).print(format!(
+            "Targeted items: {} tracked, {} due, {} graduated",
+            srs_items.len(),
+            due,
+            graduated
+        ));
     }
 
-    Ok(())
+    row += 2;
+    frame.move_to(2, row).print(format!(
+        "Tab for {} stats, any other key to return...",
+        other
+    ));
+
+    display.present(frame)
 }
@@ -0,0 +1,186 @@
+use crate::custom_text::{self, TextOptions};
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const ENGLISH_CORPUS: &str = include_str!("../data/corpus/english.txt");
+
+// Extra weight per weak bigram a candidate word contains
+const WEAK_BIGRAM_BOOST: f64 = 3.0;
+// Chance of falling back to single-word context when the two-word context
+// has only one continuation - keeps small corpora from reciting verbatim
+const BACKOFF_CHANCE: f64 = 0.35;
+
+/// A word-level Markov chain: the next word is chosen from those that
+/// followed the previous two (or, for variety, one) words in the corpus.
+/// Punctuation and capitals stay attached to words, so output keeps the
+/// corpus's sentence shape.
+pub struct MarkovChain {
+    bigram_next: HashMap<(String, String), Vec<String>>,
+    word_next: HashMap<String, Vec<String>>,
+    // Opening word pairs of every sentence
+    starts: Vec<(String, String)>,
+}
+
+impl MarkovChain {
+    pub fn english() -> Self {
+        Self::train(ENGLISH_CORPUS)
+    }
+
+    /// `~/.typehero/corpus`, where users keep documents to train on.
+    pub fn user_dir() -> Option<PathBuf> {
+        Some(dirs::home_dir()?.join(".typehero/corpus"))
+    }
+
+    /// Train on every `.txt`/`.md` file in the user corpus directory, keeping
+    /// its accents so the prose suits the language it is written in. Returns
+    /// None if there is nothing usable there.
+    pub fn user() -> Option<Self> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(Self::user_dir()?)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| matches!(ext, "txt" | "md"))
+            })
+            .collect();
+        paths.sort();
+
+        let options = TextOptions {
+            keyboard_only: false,
+            chunk_chars: usize::MAX,
+        };
+        let text: Vec<String> = paths
+            .iter()
+            .filter_map(|p| fs::read_to_string(p).ok())
+            .flat_map(|raw| custom_text::prepare(&custom_text::plain_punctuation(&raw), &options))
+            .collect();
+
+        let chain = Self::train(&text.join("\n"));
+        if chain.starts.is_empty() {
+            None
+        } else {
+            Some(chain)
+        }
+    }
+
+    pub fn train(corpus: &str) -> Self {
+        let mut chain = Self {
+            bigram_next: HashMap::new(),
+            word_next: HashMap::new(),
+            starts: Vec::new(),
+        };
+
+        let words: Vec<&str> = corpus
+            .lines()
+            .filter(|line| !line.starts_with("# "))
+            .flat_map(str::split_whitespace)
+            .collect();
+
+        for (i, window) in words.windows(2).enumerate() {
+            let (a, b) = (window[0], window[1]);
+            chain
+                .word_next
+                .entry(a.to_string())
+                .or_default()
+                .push(b.to_string());
+
+            if (i == 0 || ends_sentence(words[i - 1])) && !ends_sentence(a) {
+                chain.starts.push((a.to_string(), b.to_string()));
+            }
+            if let Some(&c) = words.get(i + 2) {
+                chain
+                    .bigram_next
+                    .entry((a.to_string(), b.to_string()))
+                    .or_default()
+                    .push(c.to_string());
+            }
+        }
+
+        chain
+    }
+
+    /// Generate roughly `word_count` words, finishing the current sentence.
+    /// Words containing any of `weak_bigrams` are preferred when the chain
+    /// has a choice.
+    pub fn generate<R: Rng>(
+        &self,
+        word_count: usize,
+        weak_bigrams: &[String],
+        rng: &mut R,
+    ) -> String {
+        let Some(start) = self.starts.choose(rng) else {
+            return String::new();
+        };
+
+        let mut words = vec![start.0.clone(), start.1.clone()];
+        // Don't let a corpus without sentence breaks run forever
+        let limit = word_count + word_count / 2;
+
+        while words.len() < limit
+            && (words.len() < word_count || !ends_sentence(&words[words.len() - 1]))
+        {
+            let prev = &words[words.len() - 2];
+            let last = &words[words.len() - 1];
+
+            let mut candidates = self.bigram_next.get(&(prev.clone(), last.clone()));
+            let single = candidates.is_none_or(|c| c.iter().all(|w| w == &c[0]));
+            if single && rng.gen_bool(BACKOFF_CHANCE) {
+                candidates = self.word_next.get(last).or(candidates);
+            }
+
+            match candidates.and_then(|c| self.pick(c, weak_bigrams, rng)) {
+                Some(next) => words.push(next.clone()),
+                // Dead end (end of the corpus) - start a fresh sentence
+                None => {
+                    if !ends_sentence(last) {
+                        let last = words.len() - 1;
+                        words[last].push('.');
+                    }
+                    let start = self.starts.choose(rng).unwrap();
+                    words.push(start.0.clone());
+                    words.push(start.1.clone());
+                }
+            }
+        }
+
+        words.join(" ")
+    }
+
+    fn pick<'a, R: Rng>(
+        &self,
+        candidates: &'a [String],
+        weak_bigrams: &[String],
+        rng: &mut R,
+    ) -> Option<&'a String> {
+        if weak_bigrams.is_empty() {
+            return candidates.choose(rng);
+        }
+
+        let weights: Vec<f64> = candidates
+            .iter()
+            .map(|word| {
+                // Pad with spaces so bigrams like "e " or " t" can match
+                let padded = format!(" {} ", word);
+                let hits = weak_bigrams
+                    .iter()
+                    .filter(|b| padded.contains(b.as_str()))
+                    .count();
+                1.0 + WEAK_BIGRAM_BOOST * hits as f64
+            })
+            .collect();
+        let dist = WeightedIndex::new(&weights).ok()?;
+        Some(&candidates[dist.sample(rng)])
+    }
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')'])
+        .ends_with(['.', '?', '!'])
+}
@@ -0,0 +1,334 @@
+use crate::display::{grapheme_width, TAB_WIDTH};
+use crossterm::{
+    cursor,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, ClearType},
+    QueueableCommand,
+};
+use std::fmt;
+use std::io::{stdout, Stdout, Write};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Colours and attributes for one cell. `None` is the terminal's default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            bold: false,
+            underline: false,
+            reverse: false,
+        }
+    }
+
+    pub const fn fg(self, color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn bg(self, color: Color) -> Self {
+        Self {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    pub const fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    pub const fn reverse(self) -> Self {
+        Self {
+            reverse: true,
+            ..self
+        }
+    }
+}
+
+/// One terminal cell. A wide grapheme is stored in its first cell, and the
+/// cells it spills into hold an empty symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub symbol: String,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+impl Cell {
+    fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+}
+
+/// An off-screen grid that screens draw into before handing it to a
+/// `Renderer`. Drawing goes through a pen, the same way commands are queued
+/// on stdout: move it, set colours, print. Anything outside the grid is
+/// clipped.
+pub struct Frame {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    col: u16,
+    row: u16,
+    style: Style,
+    cursor: Option<(u16, u16)>,
+}
+
+impl Frame {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+            col: 0,
+            row: 0,
+            style: Style::default(),
+            cursor: None,
+        }
+    }
+
+    pub fn move_to(&mut self, col: u16, row: u16) -> &mut Self {
+        self.col = col;
+        self.row = row;
+        self
+    }
+
+    pub fn style(&mut self, style: Style) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    pub fn reset(&mut self) -> &mut Self {
+        self.style = Style::default();
+        self
+    }
+
+    /// Write text at the pen and advance it by the text's display width.
+    /// Tabs are expanded and line breaks dropped.
+    pub fn print<T: fmt::Display>(&mut self, text: T) -> &mut Self {
+        let text = text.to_string();
+        for grapheme in text.graphemes(true) {
+            match grapheme {
+                "\n" | "\r" | "\r\n" => {}
+                "\t" => {
+                    for _ in 0..TAB_WIDTH {
+                        self.put(" ", 1);
+                    }
+                }
+                _ => self.put(grapheme, grapheme_width(grapheme)),
+            }
+        }
+        self
+    }
+
+    /// Show the terminal cursor at this cell once the frame is drawn. Frames
+    /// that don't set it hide the cursor.
+    pub fn set_cursor(&mut self, col: u16, row: u16) -> &mut Self {
+        self.cursor = Some((col, row));
+        self
+    }
+
+    fn index(&self, col: u16, row: u16) -> usize {
+        row as usize * self.width as usize + col as usize
+    }
+
+    fn cell(&self, col: u16, row: u16) -> &Cell {
+        &self.cells[self.index(col, row)]
+    }
+
+    fn put(&mut self, symbol: &str, width: usize) {
+        let (col, row) = (self.col, self.row);
+        if row >= self.height {
+            return;
+        }
+
+        // Zero-width graphemes join whatever is to their left
+        if width == 0 {
+            if col > 0 && col <= self.width {
+                let mut lead = col - 1;
+                while lead > 0 && self.cell(lead, row).is_continuation() {
+                    lead -= 1;
+                }
+                let index = self.index(lead, row);
+                self.cells[index].symbol.push_str(symbol);
+            }
+            return;
+        }
+
+        self.col = self.col.saturating_add(width as u16);
+        if col as usize + width > self.width as usize {
+            return;
+        }
+
+        for c in col..col + width as u16 {
+            self.split_wide(c, row);
+        }
+        let index = self.index(col, row);
+        self.cells[index] = Cell {
+            symbol: symbol.to_string(),
+            style: self.style,
+        };
+        for c in col + 1..col + width as u16 {
+            let index = self.index(c, row);
+            self.cells[index] = Cell {
+                symbol: String::new(),
+                style: self.style,
+            };
+        }
+    }
+
+    /// Blank out any wide grapheme covering this cell, so overwriting half
+    /// of one doesn't leave the other half behind.
+    fn split_wide(&mut self, col: u16, row: u16) {
+        let mut lead = col;
+        while lead > 0 && self.cell(lead, row).is_continuation() {
+            lead -= 1;
+        }
+        let end = lead + grapheme_width(&self.cell(lead, row).symbol).max(1) as u16;
+        if end - lead == 1 {
+            return;
+        }
+        for c in lead..end.min(self.width) {
+            let index = self.index(c, row);
+            self.cells[index] = Cell::default();
+        }
+    }
+}
+
+/// Draws frames to the terminal, sending only the cells that differ from
+/// the previous frame. Nothing is written when a frame is unchanged.
+#[derive(Default)]
+pub struct Renderer {
+    previous: Option<Frame>,
+    cursor_visible: Option<bool>,
+}
+
+impl Renderer {
+    /// Forget what is on screen so the next frame is drawn in full.
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn draw(&mut self, frame: Frame) -> Result<(), Box<dyn std::error::Error>> {
+        let mut out = stdout();
+        let previous = self
+            .previous
+            .take()
+            .filter(|p| p.width == frame.width && p.height == frame.height);
+
+        if previous.is_none() {
+            out.queue(terminal::Clear(ClearType::All))?;
+        }
+
+        // Where the terminal cursor is after the last print, if we know
+        let mut pen: Option<(u16, u16)> = None;
+        let mut style: Option<Style> = None;
+        let mut changed = previous.is_none();
+
+        for row in 0..frame.height {
+            for col in 0..frame.width {
+                let cell = frame.cell(col, row);
+                if cell.is_continuation() {
+                    continue;
+                }
+                let unchanged = match &previous {
+                    Some(previous) => previous.cell(col, row) == cell,
+                    // The screen was just cleared
+                    None => *cell == Cell::default(),
+                };
+                if unchanged {
+                    continue;
+                }
+
+                if pen != Some((col, row)) {
+                    out.queue(cursor::MoveTo(col, row))?;
+                }
+                if style != Some(cell.style) {
+                    queue_style(&mut out, cell.style)?;
+                    style = Some(cell.style);
+                }
+                out.queue(Print(&cell.symbol))?;
+                changed = true;
+
+                // Terminals disagree about the width of some non-ASCII
+                // graphemes, so only trust the cursor after plain ASCII
+                pen = if cell.symbol.is_ascii() {
+                    Some((col + 1, row))
+                } else {
+                    None
+                };
+            }
+        }
+
+        if style.is_some() {
+            out.queue(SetAttribute(Attribute::Reset))?;
+        }
+        let cursor_moved = previous.as_ref().is_none_or(|p| p.cursor != frame.cursor);
+        match frame.cursor {
+            Some((col, row)) if changed || cursor_moved => {
+                out.queue(cursor::MoveTo(col, row))?;
+            }
+            _ => {}
+        }
+        let visible = frame.cursor.is_some();
+        if self.cursor_visible != Some(visible) {
+            if visible {
+                out.queue(cursor::Show)?;
+            } else {
+                out.queue(cursor::Hide)?;
+            }
+            self.cursor_visible = Some(visible);
+        }
+
+        out.flush()?;
+        self.previous = Some(frame);
+        Ok(())
+    }
+}
+
+fn queue_style(out: &mut Stdout, style: Style) -> Result<(), Box<dyn std::error::Error>> {
+    // Attribute::Reset clears colours too, so it has to come first
+    out.queue(SetAttribute(Attribute::Reset))?;
+    if style.bold {
+        out.queue(SetAttribute(Attribute::Bold))?;
+    }
+    if style.underline {
+        out.queue(SetAttribute(Attribute::Underlined))?;
+    }
+    if style.reverse {
+        out.queue(SetAttribute(Attribute::Reverse))?;
+    }
+    if let Some(fg) = style.fg {
+        out.queue(SetForegroundColor(fg))?;
+    }
+    if let Some(bg) = style.bg {
+        out.queue(SetBackgroundColor(bg))?;
+    }
+    Ok(())
+}
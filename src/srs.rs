@@ -0,0 +1,151 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+
+const DEFAULT_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Bigram,
+    Key,
+}
+
+impl ItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Bigram => "bigram",
+            ItemKind::Key => "key",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "key" => ItemKind::Key,
+            _ => ItemKind::Bigram,
+        }
+    }
+}
+
+/// A weak bigram or key scheduled for review (SM-2 style).
+#[derive(Debug, Clone)]
+pub struct SrsItem {
+    pub pattern: String,
+    pub kind: ItemKind,
+    pub interval_days: f64,
+    pub ease: f64,
+    pub repetitions: u32,
+    pub due: DateTime<Utc>,
+}
+
+impl SrsItem {
+    pub fn new(pattern: String, kind: ItemKind) -> Self {
+        Self {
+            pattern,
+            kind,
+            interval_days: 0.0,
+            ease: DEFAULT_EASE,
+            repetitions: 0,
+            due: Utc::now(),
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.due <= now
+    }
+
+    /// Apply a review graded 0-5. Failed reviews (< 3) stay due so the item
+    /// comes straight back next session; passed reviews push the due date out.
+    pub fn review(&mut self, quality: u8, now: DateTime<Utc>) {
+        let q = quality.min(5) as f64;
+
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval_days = 0.0;
+        } else {
+            self.repetitions += 1;
+            self.interval_days = match self.repetitions {
+                1 => 1.0,
+                2 => 6.0,
+                _ => self.interval_days * self.ease,
+            };
+        }
+
+        self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(MIN_EASE);
+        self.due = now + ChronoDuration::seconds((self.interval_days * 86_400.0) as i64);
+    }
+}
+
+/// What a single session measured for one key or bigram.
+#[derive(Debug, Clone, Default)]
+pub struct PatternSample {
+    pub hits: usize,
+    pub misses: usize,
+    pub total_ms: f64,
+    pub timed: usize,
+}
+
+impl PatternSample {
+    pub fn record(&mut self, correct: bool, latency_ms: Option<f64>) {
+        if correct {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        if let Some(ms) = latency_ms {
+            self.total_ms += ms;
+            self.timed += 1;
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 1.0;
+        }
+        self.hits as f64 / total as f64
+    }
+
+    pub fn avg_latency_ms(&self) -> Option<f64> {
+        if self.timed == 0 {
+            None
+        } else {
+            Some(self.total_ms / self.timed as f64)
+        }
+    }
+}
+
+/// Grade a session's performance on an item. Accuracy gates everything;
+/// bigrams must also be typed at or below the user's overall bigram latency.
+pub fn grade(kind: ItemKind, sample: &PatternSample, baseline_ms: f64) -> u8 {
+    let accuracy = sample.accuracy();
+    let accuracy_grade = if accuracy < 0.8 {
+        1
+    } else if accuracy < 0.95 {
+        2
+    } else if accuracy < 1.0 {
+        4
+    } else {
+        5
+    };
+
+    if kind == ItemKind::Key || baseline_ms <= 0.0 {
+        return accuracy_grade;
+    }
+
+    let latency_grade = match sample.avg_latency_ms() {
+        Some(ms) => {
+            let ratio = ms / baseline_ms;
+            if ratio <= 0.9 {
+                5
+            } else if ratio <= 1.1 {
+                4
+            } else if ratio <= 1.5 {
+                3
+            } else {
+                2
+            }
+        }
+        None => accuracy_grade,
+    };
+
+    accuracy_grade.min(latency_grade)
+}
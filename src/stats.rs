@@ -1,3 +1,4 @@
+use crate::srs::{self, ItemKind, PatternSample, SrsItem};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, Result as SqlResult};
 use serde::{Deserialize, Serialize};
@@ -5,6 +6,10 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// Stats language for code practice, so it isn't mixed into a natural
+/// language's stats
+pub const CODE_LANGUAGE: &str = "code";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionSummary {
     pub timestamp: DateTime<Utc>,
@@ -14,10 +19,50 @@ pub struct SessionSummary {
     pub raw_wpm: f64,
     pub accuracy: f64,
     pub adjusted_wpm: f64,
+    #[serde(default)]
+    pub modifiers: String,
+    /// Practice mode the session came from when it isn't plain text
+    /// practice, e.g. "codejam"
+    #[serde(default)]
+    pub mode: String,
+}
+
+/// Best completed run of a specific item (a quote, a snippet, ...).
+#[derive(Debug, Clone)]
+pub struct PersonalBest {
+    pub adjusted_wpm: f64,
+    pub accuracy: f64,
+    pub timestamp: DateTime<Utc>,
 }
 
+// Tables keyed per language. Databases from before language packs have
+// these without the `language` column and are rebuilt on load.
+const BIGRAMS_SCHEMA: &str = "
+    language TEXT NOT NULL DEFAULT 'en',
+    pair TEXT NOT NULL,
+    total_time_us INTEGER NOT NULL,
+    sample_count INTEGER NOT NULL,
+    avg_time_ms REAL NOT NULL,
+    PRIMARY KEY (language, pair)";
+const KEY_ERRORS_SCHEMA: &str = "
+    language TEXT NOT NULL DEFAULT 'en',
+    key TEXT NOT NULL,
+    error_count INTEGER NOT NULL,
+    PRIMARY KEY (language, key)";
+const SRS_ITEMS_SCHEMA: &str = "
+    language TEXT NOT NULL DEFAULT 'en',
+    pattern TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    interval_days REAL NOT NULL,
+    ease REAL NOT NULL,
+    repetitions INTEGER NOT NULL,
+    due TEXT NOT NULL,
+    PRIMARY KEY (language, pattern)";
+
 pub struct Stats {
     conn: Connection,
+    // Language code that keystroke, bigram and session stats are kept under
+    language: String,
 }
 
 impl Stats {
@@ -38,7 +83,7 @@ impl Stats {
         let conn = Connection::open(&path).expect("Failed to open database");
 
         // Create tables if they don't exist
-        conn.execute_batch(
+        conn.execute_batch(&format!(
             "
             CREATE TABLE IF NOT EXISTS sessions (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -53,27 +98,47 @@ impl Stats {
 
             CREATE INDEX IF NOT EXISTS idx_sessions_timestamp ON sessions(timestamp);
 
-            CREATE TABLE IF NOT EXISTS bigrams (
-                pair TEXT PRIMARY KEY,
-                total_time_us INTEGER NOT NULL,
-                sample_count INTEGER NOT NULL,
-                avg_time_ms REAL NOT NULL
-            );
+            CREATE TABLE IF NOT EXISTS bigrams ({BIGRAMS_SCHEMA});
 
-            CREATE TABLE IF NOT EXISTS key_errors (
-                key TEXT PRIMARY KEY,
-                error_count INTEGER NOT NULL
-            );
+            CREATE TABLE IF NOT EXISTS key_errors ({KEY_ERRORS_SCHEMA});
 
             CREATE TABLE IF NOT EXISTS metadata (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
             );
-            ",
-        )
+
+            CREATE TABLE IF NOT EXISTS srs_items ({SRS_ITEMS_SCHEMA});
+
+            CREATE TABLE IF NOT EXISTS personal_bests (
+                kind TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                adjusted_wpm REAL NOT NULL,
+                accuracy REAL NOT NULL,
+                timestamp TEXT NOT NULL,
+                PRIMARY KEY (kind, item_id)
+            );
+            "
+        ))
         .expect("Failed to create tables");
 
-        let mut stats = Self { conn };
+        let mut stats = Self {
+            conn,
+            language: "en".to_string(),
+        };
+        stats.ensure_column("sessions", "modifiers", "TEXT NOT NULL DEFAULT ''");
+        stats.ensure_column("sessions", "language", "TEXT NOT NULL DEFAULT 'en'");
+        stats.ensure_column("sessions", "mode", "TEXT NOT NULL DEFAULT ''");
+        stats.ensure_language_key(
+            "bigrams",
+            BIGRAMS_SCHEMA,
+            "pair, total_time_us, sample_count, avg_time_ms",
+        );
+        stats.ensure_language_key("key_errors", KEY_ERRORS_SCHEMA, "key, error_count");
+        stats.ensure_language_key(
+            "srs_items",
+            SRS_ITEMS_SCHEMA,
+            "pattern, kind, interval_days, ease, repetitions, due",
+        );
 
         // Migrate from legacy JSON if it exists and DB is empty
         if Self::legacy_json_file().exists() {
@@ -85,6 +150,64 @@ impl Stats {
         stats
     }
 
+    fn has_column(&self, table: &str, column: &str) -> bool {
+        self.conn
+            .prepare(&format!(
+                "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+                table
+            ))
+            .and_then(|mut stmt| stmt.exists(params![column]))
+            .unwrap_or(false)
+    }
+
+    /// Add a column to an existing table if an older database lacks it.
+    fn ensure_column(&self, table: &str, column: &str, definition: &str) {
+        if !self.has_column(table, column) {
+            let _ = self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            );
+        }
+    }
+
+    /// Rebuild a table from before language packs so `language` is part of
+    /// its primary key. Existing rows are kept as English.
+    fn ensure_language_key(&self, table: &str, schema: &str, columns: &str) {
+        if self.has_column(table, "language") {
+            return;
+        }
+
+        let migration = format!(
+            "BEGIN;
+             ALTER TABLE {table} RENAME TO {table}_old;
+             CREATE TABLE {table} ({schema});
+             INSERT INTO {table} ({columns}) SELECT {columns} FROM {table}_old;
+             DROP TABLE {table}_old;
+             COMMIT;"
+        );
+        if self.conn.execute_batch(&migration).is_err() {
+            let _ = self.conn.execute_batch("ROLLBACK");
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Switch which language's stats are recorded and queried.
+    pub fn set_language(&mut self, code: &str) {
+        self.language = code.to_string();
+    }
+
+    // English keeps the original metadata keys so existing totals carry over
+    fn counter_key(&self, name: &str) -> String {
+        if self.language == "en" {
+            name.to_string()
+        } else {
+            format!("{}:{}", name, self.language)
+        }
+    }
+
     fn is_empty(&self) -> bool {
         let count: i64 = self
             .conn
@@ -158,8 +281,8 @@ impl Stats {
 
     pub fn add_session(&mut self, summary: SessionSummary) -> SqlResult<()> {
         self.conn.execute(
-            "INSERT INTO sessions (timestamp, duration_secs, total_chars, errors, raw_wpm, accuracy, adjusted_wpm)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO sessions (timestamp, duration_secs, total_chars, errors, raw_wpm, accuracy, adjusted_wpm, modifiers, language, mode)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 summary.timestamp.to_rfc3339(),
                 summary.duration_secs,
@@ -168,40 +291,42 @@ impl Stats {
                 summary.raw_wpm,
                 summary.accuracy,
                 summary.adjusted_wpm,
+                summary.modifiers,
+                self.language,
+                summary.mode,
             ],
         )?;
         Ok(())
     }
 
-    pub fn record_keystroke(&mut self, ch: char, correct: bool) {
+    pub fn record_keystroke(&mut self, key: &str, correct: bool) {
         // Increment total_keys
         let total_keys = self.total_keys() + 1;
         let _ = self.conn.execute(
-            "INSERT OR REPLACE INTO metadata (key, value) VALUES ('total_keys', ?1)",
-            params![total_keys.to_string()],
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+            params![self.counter_key("total_keys"), total_keys.to_string()],
         );
 
         if !correct {
             // Increment total_errors
             let total_errors = self.total_errors() + 1;
             let _ = self.conn.execute(
-                "INSERT OR REPLACE INTO metadata (key, value) VALUES ('total_errors', ?1)",
-                params![total_errors.to_string()],
+                "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+                params![self.counter_key("total_errors"), total_errors.to_string()],
             );
 
             // Increment key-specific error count
-            let key = ch.to_string();
             self.conn
                 .execute(
-                    "INSERT INTO key_errors (key, error_count) VALUES (?1, 1)
-                     ON CONFLICT(key) DO UPDATE SET error_count = error_count + 1",
-                    params![key],
+                    "INSERT INTO key_errors (language, key, error_count) VALUES (?1, ?2, 1)
+                     ON CONFLICT(language, key) DO UPDATE SET error_count = error_count + 1",
+                    params![self.language, key],
                 )
                 .ok();
         }
     }
 
-    pub fn record_bigram(&mut self, bigram: (char, char), duration: Duration) {
+    pub fn record_bigram(&mut self, bigram: (&str, &str), duration: Duration) {
         let pair = format!("{}{}", bigram.0, bigram.1);
         let time_us = duration.as_micros() as i64;
 
@@ -209,8 +334,8 @@ impl Stats {
         let (current_total, current_count): (i64, i64) = self
             .conn
             .query_row(
-                "SELECT total_time_us, sample_count FROM bigrams WHERE pair = ?1",
-                params![pair],
+                "SELECT total_time_us, sample_count FROM bigrams WHERE language = ?1 AND pair = ?2",
+                params![self.language, pair],
                 |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .unwrap_or((0, 0));
@@ -221,9 +346,9 @@ impl Stats {
 
         self.conn
             .execute(
-                "INSERT OR REPLACE INTO bigrams (pair, total_time_us, sample_count, avg_time_ms)
-                 VALUES (?1, ?2, ?3, ?4)",
-                params![pair, new_total, new_count, new_avg_ms],
+                "INSERT OR REPLACE INTO bigrams (language, pair, total_time_us, sample_count, avg_time_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![self.language, pair, new_total, new_count, new_avg_ms],
             )
             .ok();
     }
@@ -231,11 +356,11 @@ impl Stats {
     pub fn slowest_bigrams(&self, limit: usize) -> Vec<(String, f64)> {
         let mut stmt = self
             .conn
-            .prepare("SELECT pair, avg_time_ms FROM bigrams WHERE sample_count >= 3 ORDER BY avg_time_ms DESC LIMIT ?1")
+            .prepare("SELECT pair, avg_time_ms FROM bigrams WHERE language = ?1 AND sample_count >= 3 ORDER BY avg_time_ms DESC LIMIT ?2")
             .expect("Failed to prepare query");
 
         let rows = stmt
-            .query_map(params![limit as i64], |row| {
+            .query_map(params![self.language, limit as i64], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
             })
             .expect("Failed to query bigrams");
@@ -243,7 +368,7 @@ impl Stats {
         rows.filter_map(|r| r.ok()).collect()
     }
 
-    pub fn most_error_prone_keys(&self, limit: usize) -> Vec<(char, f64)> {
+    pub fn most_error_prone_keys(&self, limit: usize) -> Vec<(String, f64)> {
         let total_keys = self.total_keys() as f64;
         if total_keys == 0.0 {
             return Vec::new();
@@ -251,21 +376,186 @@ impl Stats {
 
         let mut stmt = self
             .conn
-            .prepare("SELECT key, error_count FROM key_errors WHERE error_count >= 3 ORDER BY error_count DESC LIMIT ?1")
+            .prepare("SELECT key, error_count FROM key_errors WHERE language = ?1 AND error_count >= 3 ORDER BY error_count DESC LIMIT ?2")
             .expect("Failed to prepare query");
 
         let rows = stmt
-            .query_map(params![limit as i64], |row| {
+            .query_map(params![self.language, limit as i64], |row| {
                 let key: String = row.get(0)?;
                 let count: i64 = row.get(1)?;
                 let rate = (count as f64 / total_keys) * 100.0;
-                Ok((key.chars().next().unwrap_or(' '), rate))
+                Ok((key, rate))
             })
             .expect("Failed to query key errors");
 
         rows.filter_map(|r| r.ok()).collect()
     }
 
+    /// Overall average time between keystrokes, used as the latency baseline
+    /// when grading spaced-repetition reviews.
+    pub fn average_bigram_ms(&self) -> f64 {
+        self.conn
+            .query_row(
+                "SELECT CAST(SUM(total_time_us) AS REAL) / SUM(sample_count) / 1000.0 FROM bigrams
+                 WHERE language = ?1",
+                params![self.language],
+                |row| row.get::<_, Option<f64>>(0),
+            )
+            .ok()
+            .flatten()
+            .unwrap_or(0.0)
+    }
+
+    /// Start tracking the current weak bigrams and keys. Items already being
+    /// scheduled keep their interval and ease.
+    pub fn enroll_weak_items(&mut self, bigram_limit: usize, key_limit: usize) {
+        let mut items: Vec<SrsItem> = self
+            .slowest_bigrams(bigram_limit)
+            .into_iter()
+            .map(|(pair, _)| SrsItem::new(pair, ItemKind::Bigram))
+            .collect();
+        items.extend(
+            self.most_error_prone_keys(key_limit)
+                .into_iter()
+                .map(|(key, _)| SrsItem::new(key, ItemKind::Key)),
+        );
+
+        for item in items {
+            let _ = self.conn.execute(
+                "INSERT OR IGNORE INTO srs_items (language, pattern, kind, interval_days, ease, repetitions, due)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    self.language,
+                    item.pattern,
+                    item.kind.as_str(),
+                    item.interval_days,
+                    item.ease,
+                    item.repetitions,
+                    item.due.to_rfc3339(),
+                ],
+            );
+        }
+    }
+
+    pub fn srs_items(&self) -> Vec<SrsItem> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT pattern, kind, interval_days, ease, repetitions, due FROM srs_items WHERE language = ?1 ORDER BY due ASC")
+            .expect("Failed to prepare query");
+
+        let rows = stmt
+            .query_map(params![self.language], |row| {
+                Ok(SrsItem {
+                    pattern: row.get(0)?,
+                    kind: ItemKind::parse(&row.get::<_, String>(1)?),
+                    interval_days: row.get(2)?,
+                    ease: row.get(3)?,
+                    repetitions: row.get::<_, i64>(4)? as u32,
+                    due: DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                        .map(|d| d.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })
+            .expect("Failed to query srs items");
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    pub fn due_srs_items(&self, limit: usize) -> Vec<SrsItem> {
+        let now = Utc::now();
+        self.srs_items()
+            .into_iter()
+            .filter(|item| item.is_due(now))
+            .take(limit)
+            .collect()
+    }
+
+    /// Grade each reviewed item against what the session measured and
+    /// reschedule it. Items that never came up in the session are left alone.
+    pub fn review_srs_items(
+        &mut self,
+        items: &[SrsItem],
+        key_samples: &HashMap<String, PatternSample>,
+        bigram_samples: &HashMap<String, PatternSample>,
+    ) {
+        let baseline_ms = self.average_bigram_ms();
+        let now = Utc::now();
+
+        for item in items {
+            let sample = match item.kind {
+                ItemKind::Key => key_samples.get(&item.pattern),
+                ItemKind::Bigram => bigram_samples.get(&item.pattern),
+            };
+            let Some(sample) = sample else {
+                continue;
+            };
+
+            let mut item = item.clone();
+            item.review(srs::grade(item.kind, sample, baseline_ms), now);
+
+            let _ = self.conn.execute(
+                "UPDATE srs_items SET interval_days = ?3, ease = ?4, repetitions = ?5, due = ?6
+                 WHERE language = ?1 AND pattern = ?2",
+                params![
+                    self.language,
+                    item.pattern,
+                    item.interval_days,
+                    item.ease,
+                    item.repetitions,
+                    item.due.to_rfc3339(),
+                ],
+            );
+        }
+    }
+
+    pub fn personal_best(&self, kind: &str, item_id: &str) -> Option<PersonalBest> {
+        self.conn
+            .query_row(
+                "SELECT adjusted_wpm, accuracy, timestamp FROM personal_bests
+                 WHERE kind = ?1 AND item_id = ?2",
+                params![kind, item_id],
+                |row| {
+                    Ok(PersonalBest {
+                        adjusted_wpm: row.get(0)?,
+                        accuracy: row.get(1)?,
+                        timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
+                            .map(|d| d.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                },
+            )
+            .ok()
+    }
+
+    /// Store `summary` as the best run of an item if it beats the previous
+    /// best adjusted WPM. Returns true when a new best was set.
+    pub fn record_personal_best(
+        &mut self,
+        kind: &str,
+        item_id: &str,
+        summary: &SessionSummary,
+    ) -> bool {
+        self.conn
+            .execute(
+                "INSERT INTO personal_bests (kind, item_id, adjusted_wpm, accuracy, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(kind, item_id) DO UPDATE SET
+                     adjusted_wpm = excluded.adjusted_wpm,
+                     accuracy = excluded.accuracy,
+                     timestamp = excluded.timestamp
+                 WHERE excluded.adjusted_wpm > personal_bests.adjusted_wpm",
+                params![
+                    kind,
+                    item_id,
+                    summary.adjusted_wpm,
+                    summary.accuracy,
+                    summary.timestamp.to_rfc3339(),
+                ],
+            )
+            .map(|changed| changed > 0)
+            .unwrap_or(false)
+    }
+
     pub fn lifetime_accuracy(&self) -> f64 {
         let total_keys = self.total_keys();
         let total_errors = self.total_errors();
@@ -279,21 +569,23 @@ impl Stats {
     pub fn average_adjusted_wpm(&self) -> f64 {
         self.conn
             .query_row(
-                "SELECT AVG(adjusted_wpm) FROM sessions",
-                [],
-                |row| row.get(0),
+                "SELECT AVG(adjusted_wpm) FROM sessions WHERE language = ?1",
+                params![self.language],
+                |row| row.get::<_, Option<f64>>(0),
             )
+            .ok()
+            .flatten()
             .unwrap_or(0.0)
     }
 
     pub fn sessions(&self) -> Vec<SessionSummary> {
         let mut stmt = self
             .conn
-            .prepare("SELECT timestamp, duration_secs, total_chars, errors, raw_wpm, accuracy, adjusted_wpm FROM sessions ORDER BY timestamp DESC")
+            .prepare("SELECT timestamp, duration_secs, total_chars, errors, raw_wpm, accuracy, adjusted_wpm, modifiers, mode FROM sessions WHERE language = ?1 ORDER BY timestamp DESC")
             .expect("Failed to prepare query");
 
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params![self.language], |row| {
                 Ok(SessionSummary {
                     timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
                         .unwrap()
@@ -304,6 +596,8 @@ impl Stats {
                     raw_wpm: row.get(4)?,
                     accuracy: row.get(5)?,
                     adjusted_wpm: row.get(6)?,
+                    modifiers: row.get(7)?,
+                    mode: row.get(8)?,
                 })
             })
             .expect("Failed to query sessions");
@@ -314,8 +608,8 @@ impl Stats {
     fn total_keys(&self) -> usize {
         self.conn
             .query_row(
-                "SELECT value FROM metadata WHERE key = 'total_keys'",
-                [],
+                "SELECT value FROM metadata WHERE key = ?1",
+                params![self.counter_key("total_keys")],
                 |row| row.get::<_, String>(0),
             )
             .ok()
@@ -326,8 +620,8 @@ impl Stats {
     fn total_errors(&self) -> usize {
         self.conn
             .query_row(
-                "SELECT value FROM metadata WHERE key = 'total_errors'",
-                [],
+                "SELECT value FROM metadata WHERE key = ?1",
+                params![self.counter_key("total_errors")],
                 |row| row.get::<_, String>(0),
             )
             .ok()
@@ -347,6 +641,8 @@ impl Stats {
 impl Clone for Stats {
     fn clone(&self) -> Self {
         // For cloning, we reconnect to the same database
-        Self::load()
+        let mut stats = Self::load();
+        stats.language = self.language.clone();
+        stats
     }
 }
@@ -0,0 +1,215 @@
+use crate::guided_v2::Finger;
+use crate::render::Style;
+use crossterm::style::Color;
+
+/// A colour scheme: one style per role on screen, so screens never name
+/// colours directly.
+pub struct Theme {
+    pub name: &'static str,
+    /// Title bars and headings
+    pub title: Style,
+    pub text: Style,
+    /// Hints and not-yet-typed text
+    pub dim: Style,
+    /// Live stats, section headings, the quit warning
+    pub accent: Style,
+    /// The selected menu entry and the character to type next
+    pub selected: Style,
+    pub correct: Style,
+    pub incorrect: Style,
+    /// Scores that meet the 95% target, fall short, or fail badly
+    pub good: Style,
+    pub ok: Style,
+    pub bad: Style,
+    /// Pinky, ring, middle, index, thumbs
+    pub fingers: [Style; 5],
+    /// syntect theme for CodeJam, or None to draw code in `text`
+    pub syntax_theme: Option<&'static str>,
+}
+
+const DEFAULT: Theme = Theme {
+    name: "default",
+    title: Style::new().fg(Color::Cyan),
+    text: Style::new().fg(Color::White),
+    dim: Style::new().fg(Color::DarkGrey),
+    accent: Style::new().fg(Color::Yellow),
+    selected: Style::new().fg(Color::White).bg(Color::DarkBlue),
+    correct: Style::new().fg(Color::Green),
+    incorrect: Style::new().fg(Color::Red).bg(Color::DarkRed),
+    good: Style::new().fg(Color::Green),
+    ok: Style::new().fg(Color::Yellow),
+    bad: Style::new().fg(Color::Red),
+    fingers: [
+        Style::new().fg(Color::Magenta),
+        Style::new().fg(Color::Blue),
+        Style::new().fg(Color::Cyan),
+        Style::new().fg(Color::Green),
+        Style::new().fg(Color::Yellow),
+    ],
+    syntax_theme: Some("base16-ocean.dark"),
+};
+
+// Darker shades that stay readable on a white background
+const LIGHT: Theme = Theme {
+    name: "light",
+    title: Style::new().fg(Color::DarkBlue),
+    text: Style::new().fg(Color::Black),
+    dim: Style::new().fg(Color::Grey),
+    accent: Style::new().fg(Color::DarkYellow),
+    selected: Style::new().fg(Color::White).bg(Color::DarkBlue),
+    correct: Style::new().fg(Color::DarkGreen),
+    incorrect: Style::new().fg(Color::White).bg(Color::DarkRed),
+    good: Style::new().fg(Color::DarkGreen),
+    ok: Style::new().fg(Color::DarkYellow),
+    bad: Style::new().fg(Color::DarkRed),
+    fingers: [
+        Style::new().fg(Color::DarkMagenta),
+        Style::new().fg(Color::DarkBlue),
+        Style::new().fg(Color::DarkCyan),
+        Style::new().fg(Color::DarkGreen),
+        Style::new().fg(Color::DarkYellow),
+    ],
+    syntax_theme: Some("InspiredGitHub"),
+};
+
+const fn rgb(r: u8, g: u8, b: u8) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+// Solarized dark accents: https://ethanschoonover.com/solarized/
+const SOLARIZED: Theme = Theme {
+    name: "solarized",
+    title: Style::new().fg(rgb(38, 139, 210)),
+    text: Style::new().fg(rgb(147, 161, 161)),
+    dim: Style::new().fg(rgb(88, 110, 117)),
+    accent: Style::new().fg(rgb(181, 137, 0)),
+    selected: Style::new().fg(rgb(253, 246, 227)).bg(rgb(38, 139, 210)),
+    correct: Style::new().fg(rgb(133, 153, 0)),
+    incorrect: Style::new().fg(rgb(220, 50, 47)).bg(rgb(7, 54, 66)),
+    good: Style::new().fg(rgb(133, 153, 0)),
+    ok: Style::new().fg(rgb(181, 137, 0)),
+    bad: Style::new().fg(rgb(220, 50, 47)),
+    fingers: [
+        Style::new().fg(rgb(211, 54, 130)),
+        Style::new().fg(rgb(108, 113, 196)),
+        Style::new().fg(rgb(42, 161, 152)),
+        Style::new().fg(rgb(133, 153, 0)),
+        Style::new().fg(rgb(181, 137, 0)),
+    ],
+    syntax_theme: Some("Solarized (dark)"),
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    name: "high-contrast",
+    title: Style::new().fg(Color::White).bold(),
+    text: Style::new().fg(Color::White),
+    dim: Style::new().fg(Color::Grey),
+    accent: Style::new().fg(Color::Yellow).bold(),
+    selected: Style::new().fg(Color::Black).bg(Color::Yellow).bold(),
+    correct: Style::new().fg(Color::Green).bold(),
+    incorrect: Style::new()
+        .fg(Color::White)
+        .bg(Color::Red)
+        .bold()
+        .underline(),
+    good: Style::new().fg(Color::Green).bold(),
+    ok: Style::new().fg(Color::Yellow).bold(),
+    bad: Style::new().fg(Color::Red).bold(),
+    fingers: [
+        Style::new().fg(Color::Magenta).bold(),
+        Style::new().fg(Color::Blue).bold(),
+        Style::new().fg(Color::Cyan).bold(),
+        Style::new().fg(Color::Green).bold(),
+        Style::new().fg(Color::Yellow).bold(),
+    ],
+    syntax_theme: Some("base16-eighties.dark"),
+};
+
+// Okabe-Ito palette, which stays distinct under the common forms of colour
+// blindness. Mistakes are also underlined on a solid block, so they never
+// rely on hue alone.
+const COLORBLIND: Theme = Theme {
+    name: "colorblind",
+    title: Style::new().fg(rgb(86, 180, 233)),
+    text: Style::new().fg(Color::White),
+    dim: Style::new().fg(Color::DarkGrey),
+    accent: Style::new().fg(rgb(240, 228, 66)),
+    selected: Style::new().fg(Color::Black).bg(rgb(86, 180, 233)),
+    correct: Style::new().fg(rgb(86, 180, 233)),
+    incorrect: Style::new()
+        .fg(Color::Black)
+        .bg(rgb(230, 159, 0))
+        .underline(),
+    good: Style::new().fg(rgb(86, 180, 233)),
+    ok: Style::new().fg(rgb(240, 228, 66)),
+    bad: Style::new().fg(rgb(230, 159, 0)).underline(),
+    fingers: [
+        Style::new().fg(rgb(204, 121, 167)),
+        Style::new().fg(rgb(0, 114, 178)),
+        Style::new().fg(rgb(86, 180, 233)),
+        Style::new().fg(rgb(0, 158, 115)),
+        Style::new().fg(rgb(240, 228, 66)),
+    ],
+    syntax_theme: Some("base16-ocean.dark"),
+};
+
+// Attributes only, for NO_COLOR and monochrome terminals
+const NO_COLOR: Theme = Theme {
+    name: "no-color",
+    title: Style::new().bold(),
+    text: Style::new(),
+    dim: Style::new(),
+    accent: Style::new().bold(),
+    selected: Style::new().reverse(),
+    correct: Style::new(),
+    incorrect: Style::new().reverse().underline(),
+    good: Style::new().bold(),
+    ok: Style::new(),
+    bad: Style::new().underline(),
+    fingers: [Style::new().bold(); 5],
+    syntax_theme: None,
+};
+
+pub const THEMES: &[Theme] = &[
+    DEFAULT,
+    LIGHT,
+    SOLARIZED,
+    HIGH_CONTRAST,
+    COLORBLIND,
+    NO_COLOR,
+];
+
+impl Theme {
+    pub fn find(name: &str) -> Option<&'static Theme> {
+        THEMES.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The named theme, unless `NO_COLOR` is set (https://no-color.org/).
+    pub fn resolve(name: &str) -> Option<&'static Theme> {
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Self::find(NO_COLOR.name);
+        }
+        Self::find(name)
+    }
+
+    /// Style for an accuracy figure against the 95% target.
+    pub fn accuracy(&self, accuracy: f64) -> Style {
+        if accuracy >= 95.0 {
+            self.good
+        } else if accuracy >= 85.0 {
+            self.ok
+        } else {
+            self.bad
+        }
+    }
+
+    pub fn finger(&self, finger: &Finger) -> Style {
+        match finger {
+            Finger::LeftPinky | Finger::RightPinky => self.fingers[0],
+            Finger::LeftRing | Finger::RightRing => self.fingers[1],
+            Finger::LeftMiddle | Finger::RightMiddle => self.fingers[2],
+            Finger::LeftIndex | Finger::RightIndex => self.fingers[3],
+            Finger::Thumbs => self.fingers[4],
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const COMMON_WORDS: &str = include_str!("../data/wordlists/common.txt");
+const CODE_PATTERNS: &str = include_str!("../data/wordlists/code.txt");
+
+// Lines with more words than this are treated as running text and split up
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// A named vocabulary that word-based exercises draw from.
+///
+/// Files have one entry per line (short phrases allowed); lines starting with
+/// "# " are comments. Longer lines are treated as prose, so plain text files
+/// and documents work as corpora too.
+#[derive(Debug, Clone)]
+pub struct WordList {
+    pub name: String,
+    pub words: Vec<String>,
+}
+
+impl WordList {
+    pub fn common() -> Self {
+        Self::parse("common", COMMON_WORDS)
+    }
+
+    pub fn code() -> Self {
+        Self::parse("code", CODE_PATTERNS)
+    }
+
+    pub fn parse(name: &str, content: &str) -> Self {
+        let mut words = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line == "#" || line.starts_with("# ") {
+                continue;
+            }
+
+            if line.split_whitespace().count() > MAX_PHRASE_WORDS {
+                words.extend(
+                    line.split_whitespace()
+                        .map(|w| w.trim_matches(|c: char| "\"'()[]{}.,;:!?".contains(c)))
+                        .filter(|w| !w.is_empty())
+                        .map(String::from),
+                );
+            } else {
+                words.push(line.split_whitespace().collect::<Vec<_>>().join(" "));
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            words,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read word list {}: {}", path.display(), e))?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("custom");
+
+        let list = Self::parse(name, &content);
+        if list.words.is_empty() {
+            return Err(format!("Word list {} has no words", path.display()).into());
+        }
+        Ok(list)
+    }
+
+    pub fn user_dir() -> Option<PathBuf> {
+        Some(dirs::home_dir()?.join(".typehero/wordlists"))
+    }
+
+    /// Built-in lists followed by every `*.txt` in `~/.typehero/wordlists`.
+    pub fn discover() -> Vec<WordList> {
+        let mut lists = vec![Self::common(), Self::code()];
+
+        if let Some(Ok(entries)) = Self::user_dir().map(fs::read_dir) {
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|ext| ext.to_str()) == Some("txt"))
+                .collect();
+            paths.sort();
+
+            lists.extend(paths.iter().filter_map(|p| Self::load(p).ok()));
+        }
+
+        lists
+    }
+
+    /// Resolve a `--wordlist` argument: a path to a file, or the name of a
+    /// built-in or user list.
+    pub fn find(name_or_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Path::new(name_or_path);
+        if path.is_file() {
+            return Self::load(path);
+        }
+
+        let lists = Self::discover();
+        if let Some(list) = lists.iter().find(|l| l.name == name_or_path) {
+            return Ok(list.clone());
+        }
+
+        let names: Vec<&str> = lists.iter().map(|l| l.name.as_str()).collect();
+        Err(format!(
+            "Unknown word list '{}'. Available: {}",
+            name_or_path,
+            names.join(", ")
+        )
+        .into())
+    }
+
+    pub fn random_words<R: Rng>(&self, count: usize, rng: &mut R) -> Vec<&str> {
+        if self.words.is_empty() {
+            return Vec::new();
+        }
+        (0..count)
+            .map(|_| self.words.choose(rng).unwrap().as_str())
+            .collect()
+    }
+}